@@ -7,11 +7,14 @@
 //! - `#[c2pa_pipeline]` - Wrap a function with automatic TransformContext management
 //! - `#[c2pa_source]` - Define a provenance origin (root of the chain)
 //! - `#[c2pa_transform]` - Define a provenance-preserving transformation
+//! - `#[c2pa_composite]` - Define a two-input composite (`C2paComposite` impl)
+//! - `#[derive(C2paBindable)]` - Bind a struct to manifests by hashing its fields
+//! - `#[derive(C2paCommit)]` - Canonical encoding for `record(params(...))` values
 //!
 //! ## Example
 //!
 //! ```ignore
-//! #[c2pa_pipeline(generator = "demo")]
+//! #[c2pa_pipeline(generator = "demo", signer = load_signer())]
 //! fn main() -> Result<(), TransformError> {
 //!     let start = start_c2pa()?;
 //!     let result = add_ten_c2pa(&double_c2pa(&start)?)?;
@@ -19,11 +22,15 @@
 //!     Ok(())
 //! }
 //!
-//! #[c2pa_source(signer = TestSigner)]
+//! #[c2pa_source]
 //! fn start() -> u32 { 5 }
 //!
 //! #[c2pa_transform(name = "double")]
 //! fn double(x: &u32) -> u32 { x * 2 }
+//!
+//! // Several verified inputs, each recorded as an ingredient
+//! #[c2pa_transform(name = "merge", inputs(a, b))]
+//! fn merge(a: &Table, b: &Table) -> Table { a.join(b) }
 //! ```
 
 use proc_macro::TokenStream;
@@ -34,7 +41,8 @@ use syn::{
     parse_macro_input,
     punctuated::Punctuated,
     spanned::Spanned,
-    Error, Expr, FnArg, Ident, ItemFn, Lit, Meta, Result, ReturnType, Token, Type,
+    Data, DeriveInput, Error, Expr, FnArg, Ident, ItemFn, Lit, LitStr, Meta, Result, ReturnType,
+    Token, Type,
 };
 
 // ============================================================================
@@ -42,12 +50,19 @@ use syn::{
 // ============================================================================
 
 struct PipelineAttr {
-    generator: String,
+    /// Overrides the generator; nested pipelines inherit it otherwise.
+    generator: Option<String>,
+    /// Overrides the signer; nested pipelines inherit it otherwise.
+    signer: Option<Expr>,
+    /// Overrides the hash algorithm; nested pipelines inherit it otherwise.
+    hash: Option<Ident>,
 }
 
 impl Parse for PipelineAttr {
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut generator = String::from("c2pa_pipeline");
+        let mut generator = None;
+        let mut signer = None;
+        let mut hash = None;
 
         let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
         for meta in metas {
@@ -57,16 +72,40 @@ impl Parse for PipelineAttr {
                 })?;
                 if ident == "generator" {
                     if let Expr::Lit(syn::ExprLit { lit: Lit::Str(s), .. }) = &nv.value {
-                        generator = s.value();
+                        generator = Some(s.value());
                     }
+                } else if ident == "signer" {
+                    signer = Some(nv.value.clone());
+                } else if ident == "hash" {
+                    hash = Some(parse_hash_alg(&nv.value)?);
                 }
             }
         }
 
-        Ok(PipelineAttr { generator })
+        Ok(PipelineAttr { generator, signer, hash })
     }
 }
 
+/// Map `hash = "..."` to the matching `HashAlg` variant.
+fn parse_hash_alg(value: &Expr) -> Result<Ident> {
+    let Expr::Lit(syn::ExprLit { lit: Lit::Str(s), .. }) = value else {
+        return Err(Error::new(value.span(), "expected a string literal"));
+    };
+    let variant = match s.value().as_str() {
+        "sha256" => "Sha256",
+        "sha384" => "Sha384",
+        "sha512" => "Sha512",
+        "blake3" => "Blake3",
+        _ => {
+            return Err(Error::new(
+                s.span(),
+                "unknown hash algorithm; expected sha256, sha384, sha512 or blake3",
+            ))
+        }
+    };
+    Ok(Ident::new(variant, s.span()))
+}
+
 #[proc_macro_attribute]
 pub fn c2pa_pipeline(attr: TokenStream, item: TokenStream) -> TokenStream {
     let attr = parse_macro_input!(attr as PipelineAttr);
@@ -76,14 +115,45 @@ pub fn c2pa_pipeline(attr: TokenStream, item: TokenStream) -> TokenStream {
     let fn_sig = &input_fn.sig;
     let fn_block = &input_fn.block;
     let fn_attrs = &input_fn.attrs;
-    let generator = &attr.generator;
+    let name = fn_sig.ident.to_string();
+    let generator = match &attr.generator {
+        Some(generator) => quote! { ::core::option::Option::Some(#generator) },
+        None => quote! { ::core::option::Option::None },
+    };
+    let signer = match &attr.signer {
+        Some(signer) => quote! {
+            ::core::option::Option::Some(
+                ::std::sync::Arc::new(#signer) as c2pa_primitives::SharedSigner
+            )
+        },
+        None => quote! { ::core::option::Option::None },
+    };
+    let hash = match &attr.hash {
+        Some(variant) => quote! {
+            ::core::option::Option::Some(c2pa_primitives::HashAlg::#variant)
+        },
+        None => quote! { ::core::option::Option::None },
+    };
+
+    // Async pipelines own their context, so it follows the task across threads
+    let body = if fn_sig.asyncness.is_some() {
+        quote! {
+            c2pa_primitives::with_pipeline_ctx_async(
+                #name, #generator, #signer, #hash, async move #fn_block
+            ).await
+        }
+    } else {
+        quote! {
+            c2pa_primitives::with_pipeline_ctx(#name, #generator, #signer, #hash, || {
+                #fn_block
+            })
+        }
+    };
 
     let output = quote! {
         #(#fn_attrs)*
         #fn_vis #fn_sig {
-            c2pa_primitives::with_new_ctx(#generator, || {
-                #fn_block
-            })
+            #body
         }
     };
 
@@ -137,25 +207,29 @@ pub fn c2pa_source(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
-    // Default signer
-    let signer_expr = attr.signer.unwrap_or_else(|| {
-        syn::parse_quote!(c2pa_primitives::TestSigner)
-    });
+    // Explicit signer, or the pipeline's
+    let signer = match &attr.signer {
+        Some(signer) => quote! { &#signer },
+        None => quote! { ctx.signer() },
+    };
+
+    let asyncness = &input_fn.sig.asyncness;
+    let await_call = asyncness.map(|_| quote! { .await });
 
     let output = quote! {
         // Original function
         #input_fn
 
         // Generated wrapper
-        #fn_vis fn #wrapper_name() -> ::core::result::Result<
+        #fn_vis #asyncness fn #wrapper_name() -> ::core::result::Result<
             c2pa_primitives::C2pa<#output_type, c2pa_primitives::Verified>,
             c2pa_primitives::TransformError
         > {
-            let payload = #fn_name();
+            let payload = #fn_name()#await_call;
             c2pa_primitives::with_ctx(|ctx| {
                 c2pa_primitives::C2paBuilder::new(payload)
-                    .generator(&ctx.generator)
-                    .sign(&#signer_expr)
+                    .context(ctx)
+                    .sign(#signer)
             })
         }
     };
@@ -169,15 +243,21 @@ pub fn c2pa_source(attr: TokenStream, item: TokenStream) -> TokenStream {
 
 struct C2paTransformAttr {
     name: String,
-    relationship: String,
+    relationship: LitStr,
     record_params: Vec<Ident>,
+    /// Arguments taken as verified inputs; empty means the first argument.
+    inputs: Vec<Ident>,
+    /// Overrides the pipeline's signer for this transform.
+    signer: Option<Expr>,
 }
 
 impl Parse for C2paTransformAttr {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut name = None;
-        let mut relationship = String::from("derivedFrom");
+        let mut relationship = LitStr::new("derivedFrom", input.span());
         let mut record_params = Vec::new();
+        let mut inputs = Vec::new();
+        let mut signer = None;
 
         let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
 
@@ -198,11 +278,14 @@ impl Parse for C2paTransformAttr {
                         }
                         "relationship" => {
                             if let Expr::Lit(syn::ExprLit { lit: Lit::Str(s), .. }) = &nv.value {
-                                relationship = s.value();
+                                relationship = s.clone();
                             } else {
                                 return Err(Error::new(nv.value.span(), "expected string literal"));
                             }
                         }
+                        "signer" => {
+                            signer = Some(nv.value.clone());
+                        }
                         other => {
                             return Err(Error::new(ident.span(), format!("unknown attribute: {}", other)));
                         }
@@ -216,6 +299,11 @@ impl Parse for C2paTransformAttr {
                     if ident == "record" {
                         let inner: RecordAttr = syn::parse2(list.tokens.clone())?;
                         record_params = inner.params;
+                    } else if ident == "inputs" {
+                        inputs = list
+                            .parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?
+                            .into_iter()
+                            .collect();
                     } else {
                         return Err(Error::new(ident.span(), format!("unknown attribute: {}", ident)));
                     }
@@ -232,6 +320,8 @@ impl Parse for C2paTransformAttr {
             name,
             relationship,
             record_params,
+            inputs,
+            signer,
         })
     }
 }
@@ -284,27 +374,33 @@ fn extract_result_inner(ty: &Type) -> Option<(&Type, &Type)> {
     None
 }
 
-fn relationship_to_tokens(rel: &str) -> TokenStream2 {
-    match rel {
-        "parentOf" => quote! { c2pa_primitives::IngredientRelation::ParentOf },
-        "componentOf" => quote! { c2pa_primitives::IngredientRelation::ComponentOf },
-        "inputTo" => quote! { c2pa_primitives::IngredientRelation::InputTo },
-        "derivedFrom" => quote! { c2pa_primitives::IngredientRelation::DerivedFrom },
-        "composedFrom" => quote! { c2pa_primitives::IngredientRelation::ComposedFrom },
-        _ => quote! { c2pa_primitives::IngredientRelation::DerivedFrom },
-    }
+fn relationship_to_tokens(rel: &LitStr) -> Result<TokenStream2> {
+    let variant = match rel.value().as_str() {
+        "parentOf" => quote! { ParentOf },
+        "componentOf" => quote! { ComponentOf },
+        "inputTo" => quote! { InputTo },
+        "derivedFrom" => quote! { DerivedFrom },
+        "composedFrom" => quote! { ComposedFrom },
+        other => {
+            return Err(Error::new(
+                rel.span(),
+                format!(
+                    "unknown relationship `{}`; expected one of parentOf, componentOf, \
+                     inputTo, derivedFrom, composedFrom",
+                    other
+                ),
+            ))
+        }
+    };
+    Ok(quote! { c2pa_primitives::IngredientRelation::#variant })
 }
 
 fn generate_commit_code(param_name: &Ident) -> TokenStream2 {
     quote! {
-        {
-            use ::sha2::{Sha256, Digest};
-            let bytes = format!("{:?}", &#param_name);
-            let mut hasher = Sha256::new();
-            hasher.update(bytes.as_bytes());
-            let hash: [u8; 32] = hasher.finalize().into();
-            (stringify!(#param_name).to_string(), hash)
-        }
+        c2pa_primitives::ParamOpening::new(
+            stringify!(#param_name),
+            c2pa_primitives::C2paCommit::commit_bytes(&#param_name),
+        )?
     }
 }
 
@@ -325,7 +421,15 @@ fn generate_transform(attr: &C2paTransformAttr, input_fn: &ItemFn) -> Result<Tok
     let wrapper_name = format_ident!("{}_c2pa", fn_name);
 
     // Extract function arguments
-    let args: Vec<_> = input_fn.sig.inputs.iter().collect();
+    let args = input_fn
+        .sig
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Typed(pat_type) => Ok(pat_type),
+            FnArg::Receiver(_) => Err(Error::new(arg.span(), "self receivers are not supported")),
+        })
+        .collect::<Result<Vec<_>>>()?;
     if args.is_empty() {
         return Err(Error::new(
             input_fn.sig.span(),
@@ -333,33 +437,24 @@ fn generate_transform(attr: &C2paTransformAttr, input_fn: &ItemFn) -> Result<Tok
         ));
     }
 
-    // First argument must be a reference type `&T`
-    let first_arg = match &args[0] {
-        FnArg::Typed(pat_type) => pat_type,
-        FnArg::Receiver(_) => {
-            return Err(Error::new(args[0].span(), "self receivers are not supported"));
+    // Verified inputs: those listed in `inputs(...)`, or the first argument
+    let arg_name = |arg: &syn::PatType| match arg.pat.as_ref() {
+        syn::Pat::Ident(pat) => Some(pat.ident.clone()),
+        _ => None,
+    };
+    for input in &attr.inputs {
+        if !args.iter().any(|arg| arg_name(arg).as_ref() == Some(input)) {
+            return Err(Error::new(input.span(), format!("no argument named `{}`", input)));
+        }
+    }
+    let is_input = |index: usize, arg: &syn::PatType| {
+        if attr.inputs.is_empty() {
+            index == 0
+        } else {
+            arg_name(arg).is_some_and(|name| attr.inputs.contains(&name))
         }
     };
 
-    let input_inner_type = extract_ref_type(&first_arg.ty).ok_or_else(|| {
-        Error::new(first_arg.ty.span(), "first argument must be a reference type (&T)")
-    })?;
-
-    let first_arg_pat = &first_arg.pat;
-
-    // Additional parameters (starting from index 1)
-    let extra_params: Vec<_> = args
-        .iter()
-        .skip(1)
-        .filter_map(|arg| {
-            if let FnArg::Typed(pat_type) = arg {
-                Some(pat_type)
-            } else {
-                None
-            }
-        })
-        .collect();
-
     // Extract return type
     let output_type = match &input_fn.sig.output {
         ReturnType::Type(_, ty) => ty.as_ref(),
@@ -378,82 +473,77 @@ fn generate_transform(attr: &C2paTransformAttr, input_fn: &ItemFn) -> Result<Tok
         (output_type.clone(), false)
     };
 
-    // Generate parameter declarations for wrapper function (no ctx!)
-    let wrapper_params: Vec<TokenStream2> = extra_params
-        .iter()
-        .map(|param| {
-            let pat = &param.pat;
-            let ty = &param.ty;
-            quote! { #pat: #ty }
-        })
-        .collect();
+    let relationship = relationship_to_tokens(&attr.relationship)?;
 
-    // Generate parameter passing for the original function call
-    let param_pass: Vec<TokenStream2> = extra_params
-        .iter()
-        .map(|param| {
-            let pat = &param.pat;
-            quote! { #pat }
-        })
-        .collect();
+    // Wrapper parameters keep the original order; verified inputs become
+    // `&C2pa<T, Verified>`, everything else is passed through unchanged.
+    let mut wrapper_params = Vec::new();
+    let mut ingredients = Vec::new();
+    let mut unwrap_inputs = Vec::new();
+    let mut param_pass = Vec::new();
+    for (index, arg) in args.iter().enumerate() {
+        let pat = &arg.pat;
+        let ty = &arg.ty;
+        if is_input(index, arg) {
+            let inner_type = extract_ref_type(ty).ok_or_else(|| {
+                let position = if attr.inputs.is_empty() { "first argument" } else { "verified inputs" };
+                Error::new(ty.span(), format!("{} must be a reference type (&T)", position))
+            })?;
+            wrapper_params.push(quote! {
+                #pat: &c2pa_primitives::C2pa<#inner_type, c2pa_primitives::Verified>
+            });
+            ingredients.push(quote! {
+                c2pa_primitives::IngredientRef::from_verified(#pat, #relationship)
+            });
+            unwrap_inputs.push(quote! { let #pat = #pat.payload(); });
+        } else {
+            wrapper_params.push(quote! { #pat: #ty });
+        }
+        param_pass.push(quote! { #pat });
+    }
 
     // Generate commit collection for recorded parameters
     let commit_code: Vec<TokenStream2> = attr
         .record_params
         .iter()
-        .map(|param_name| generate_commit_code(param_name))
+        .map(generate_commit_code)
         .collect();
 
     let has_commits = !commit_code.is_empty();
     let transform_name = &attr.name;
-    let relationship = relationship_to_tokens(&attr.relationship);
-
-    // Generate the original function call
-    let call_original = if param_pass.is_empty() {
-        quote! { #fn_name(#first_arg_pat) }
-    } else {
-        quote! { #fn_name(#first_arg_pat, #(#param_pass),*) }
+    let signer = match &attr.signer {
+        Some(signer) => quote! { ::core::option::Option::Some(&#signer as &dyn c2pa_primitives::Signer) },
+        None => quote! { ::core::option::Option::None },
     };
 
+    // Generate the original function call; async transforms await it
+    let asyncness = &input_fn.sig.asyncness;
+    let await_call = asyncness.map(|_| quote! { .await });
+    let call_original = quote! { #fn_name(#(#param_pass),*)#await_call };
+
     // Generate payload extraction (handle fallible vs infallible)
     let payload_extraction = if is_fallible {
         quote! {
-            let out_payload = #call_original
+            let __c2pa_out_payload = #call_original
                 .map_err(|e| c2pa_primitives::TransformError::C2pa(format!("{:?}", e)))?;
         }
     } else {
         quote! {
-            let out_payload = #call_original;
+            let __c2pa_out_payload = #call_original;
         }
     };
 
     // Generate commits collection
     let commits_collection = if has_commits {
         quote! {
-            let mut param_commits: Vec<(String, [u8; 32])> = Vec::new();
+            let mut __c2pa_param_openings: Vec<c2pa_primitives::ParamOpening> = Vec::new();
             #(
-                param_commits.push(#commit_code);
+                __c2pa_param_openings.push(#commit_code);
             )*
         }
     } else {
         quote! {
-            let param_commits: Vec<(String, [u8; 32])> = Vec::new();
-        }
-    };
-
-    // Generate wrapper function signature (NO ctx argument!)
-    let wrapper_signature = if wrapper_params.is_empty() {
-        quote! {
-            #fn_vis fn #wrapper_name(
-                input: &c2pa_primitives::C2pa<#input_inner_type, c2pa_primitives::Verified>,
-            ) -> ::core::result::Result<c2pa_primitives::C2pa<#actual_output_type, c2pa_primitives::Verified>, c2pa_primitives::TransformError>
-        }
-    } else {
-        quote! {
-            #fn_vis fn #wrapper_name(
-                input: &c2pa_primitives::C2pa<#input_inner_type, c2pa_primitives::Verified>,
-                #(#wrapper_params,)*
-            ) -> ::core::result::Result<c2pa_primitives::C2pa<#actual_output_type, c2pa_primitives::Verified>, c2pa_primitives::TransformError>
+            let __c2pa_param_openings: Vec<c2pa_primitives::ParamOpening> = Vec::new();
         }
     };
 
@@ -463,29 +553,567 @@ fn generate_transform(attr: &C2paTransformAttr, input_fn: &ItemFn) -> Result<Tok
         #input_fn
 
         // Generated wrapper function (uses thread-local ctx)
-        #wrapper_signature {
+        #fn_vis #asyncness fn #wrapper_name(
+            #(#wrapper_params),*
+        ) -> ::core::result::Result<c2pa_primitives::C2pa<#actual_output_type, c2pa_primitives::Verified>, c2pa_primitives::TransformError> {
             // Collect parameter commits BEFORE calling original function
             #commits_collection
 
-            // Extract payload from verified input
-            let #first_arg_pat = input.payload();
+            // Every verified input becomes an ingredient
+            let __c2pa_ingredients = vec![#(#ingredients),*];
+
+            // Extract payloads from verified inputs
+            #(#unwrap_inputs)*
 
             // Call the original function
             #payload_extraction
 
             // Build the provenance-aware result using thread-local context
             c2pa_primitives::with_ctx(|ctx| {
-                c2pa_primitives::transform_helper::build_transform_result(
-                    out_payload,
-                    input,
+                c2pa_primitives::transform_helper::build_multi_input_result(
+                    __c2pa_out_payload,
+                    __c2pa_ingredients,
+                    #transform_name,
+                    __c2pa_param_openings,
+                    #signer,
+                    ctx,
+                )
+            })
+        }
+    };
+
+    Ok(output)
+}
+
+// ============================================================================
+// #[c2pa_composite] - Two-input composite generating a C2paComposite impl
+// ============================================================================
+
+struct C2paCompositeAttr {
+    name: String,
+    /// Struct implementing `C2paComposite`; defaults to `<FnName>Composite`.
+    composite: Option<Ident>,
+    /// Per-input relationship overrides, keyed by argument name.
+    relationships: Vec<(Ident, LitStr)>,
+}
+
+impl Parse for C2paCompositeAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut name = None;
+        let mut composite = None;
+        let mut relationships = Vec::new();
+
+        let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+
+        for meta in metas {
+            match &meta {
+                Meta::NameValue(nv) => {
+                    let ident = nv.path.get_ident().ok_or_else(|| {
+                        Error::new(nv.path.span(), "expected identifier")
+                    })?;
+
+                    match ident.to_string().as_str() {
+                        "name" => {
+                            if let Expr::Lit(syn::ExprLit { lit: Lit::Str(s), .. }) = &nv.value {
+                                name = Some(s.value());
+                            } else {
+                                return Err(Error::new(nv.value.span(), "expected string literal"));
+                            }
+                        }
+                        "composite" => {
+                            if let Expr::Path(path) = &nv.value {
+                                composite = Some(path.path.get_ident().cloned().ok_or_else(|| {
+                                    Error::new(path.span(), "expected identifier")
+                                })?);
+                            } else {
+                                return Err(Error::new(nv.value.span(), "expected identifier"));
+                            }
+                        }
+                        other => {
+                            return Err(Error::new(ident.span(), format!("unknown attribute: {}", other)));
+                        }
+                    }
+                }
+                Meta::List(list) => {
+                    let ident = list.path.get_ident().ok_or_else(|| {
+                        Error::new(list.path.span(), "expected identifier")
+                    })?;
+
+                    if ident == "relationship" {
+                        let entries = list.parse_args_with(
+                            Punctuated::<syn::MetaNameValue, Token![,]>::parse_terminated,
+                        )?;
+                        for entry in entries {
+                            let arg = entry.path.get_ident().cloned().ok_or_else(|| {
+                                Error::new(entry.path.span(), "expected argument name")
+                            })?;
+                            let Expr::Lit(syn::ExprLit { lit: Lit::Str(s), .. }) = &entry.value else {
+                                return Err(Error::new(entry.value.span(), "expected string literal"));
+                            };
+                            relationship_to_tokens(s)?;
+                            relationships.push((arg, s.clone()));
+                        }
+                    } else {
+                        return Err(Error::new(ident.span(), format!("unknown attribute: {}", ident)));
+                    }
+                }
+                Meta::Path(path) => {
+                    return Err(Error::new(path.span(), "unexpected path-only attribute"));
+                }
+            }
+        }
+
+        let name = name.ok_or_else(|| Error::new(input.span(), "missing required `name` attribute"))?;
+
+        Ok(C2paCompositeAttr {
+            name,
+            composite,
+            relationships,
+        })
+    }
+}
+
+#[proc_macro_attribute]
+pub fn c2pa_composite(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = parse_macro_input!(attr as C2paCompositeAttr);
+    let input_fn = parse_macro_input!(item as ItemFn);
+
+    match generate_composite(&attr, &input_fn) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// `hconcat` -> `HconcatComposite`
+fn composite_struct_name(fn_name: &Ident) -> Ident {
+    let camel: String = fn_name
+        .to_string()
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect();
+    format_ident!("{}Composite", camel)
+}
+
+fn generate_composite(attr: &C2paCompositeAttr, input_fn: &ItemFn) -> Result<TokenStream2> {
+    let fn_name = &input_fn.sig.ident;
+    let fn_vis = &input_fn.vis;
+    let wrapper_name = format_ident!("{}_c2pa", fn_name);
+    if let Some(asyncness) = &input_fn.sig.asyncness {
+        return Err(Error::new(
+            asyncness.span(),
+            "c2pa_composite does not support async fn; use c2pa_transform with inputs(...)",
+        ));
+    }
+    let struct_name = attr
+        .composite
+        .clone()
+        .unwrap_or_else(|| composite_struct_name(fn_name));
+
+    // Exactly two arguments, both `&T`
+    let args = input_fn
+        .sig
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Typed(pat_type) => Ok(pat_type),
+            FnArg::Receiver(_) => Err(Error::new(arg.span(), "self receivers are not supported")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if args.len() != 2 {
+        return Err(Error::new(
+            input_fn.sig.inputs.span(),
+            "c2pa_composite requires exactly two reference arguments",
+        ));
+    }
+
+    let mut pats = Vec::new();
+    let mut input_types = Vec::new();
+    let mut relationships = Vec::new();
+    for arg in &args {
+        let syn::Pat::Ident(pat) = arg.pat.as_ref() else {
+            return Err(Error::new(arg.pat.span(), "expected an argument name"));
+        };
+        let inner_type = extract_ref_type(&arg.ty)
+            .ok_or_else(|| Error::new(arg.ty.span(), "composite inputs must be reference types (&T)"))?;
+        let relationship = match attr.relationships.iter().find(|(name, _)| *name == pat.ident) {
+            Some((_, rel)) => relationship_to_tokens(rel)?,
+            None => quote! { c2pa_primitives::IngredientRelation::ComposedFrom },
+        };
+
+        pats.push(pat.ident.clone());
+        input_types.push(inner_type);
+        relationships.push(relationship);
+    }
+    for (name, _) in &attr.relationships {
+        if !pats.contains(name) {
+            return Err(Error::new(name.span(), format!("no argument named `{}`", name)));
+        }
+    }
+
+    // Extract return type
+    let output_type = match &input_fn.sig.output {
+        ReturnType::Type(_, ty) => ty.as_ref(),
+        ReturnType::Default => {
+            return Err(Error::new(
+                input_fn.sig.span(),
+                "c2pa_composite requires a return type",
+            ));
+        }
+    };
+
+    // Check if it's a Result type (fallible function)
+    let (actual_output_type, is_fallible) = if let Some((ok_ty, _err_ty)) = extract_result_inner(output_type) {
+        (ok_ty.clone(), true)
+    } else {
+        (output_type.clone(), false)
+    };
+
+    let (a, b) = (&pats[0], &pats[1]);
+    let (a_type, b_type) = (input_types[0], input_types[1]);
+    let (a_rel, b_rel) = (&relationships[0], &relationships[1]);
+    let transform_name = &attr.name;
+
+    let payload_extraction = if is_fallible {
+        quote! {
+            let __c2pa_out_payload = #fn_name(#a.payload(), #b.payload())
+                .map_err(|e| c2pa_primitives::TransformError::C2pa(format!("{:?}", e)))?;
+        }
+    } else {
+        quote! {
+            let __c2pa_out_payload = #fn_name(#a.payload(), #b.payload());
+        }
+    };
+
+    let output = quote! {
+        // Original function (unchanged)
+        #input_fn
+
+        /// Provenance-aware composite generated by `#[c2pa_composite]`.
+        #fn_vis struct #struct_name;
+
+        impl c2pa_primitives::C2paComposite<#a_type, #b_type, #actual_output_type> for #struct_name {
+            fn compose(
+                &self,
+                #a: &c2pa_primitives::C2pa<#a_type, c2pa_primitives::Verified>,
+                #b: &c2pa_primitives::C2pa<#b_type, c2pa_primitives::Verified>,
+                ctx: &mut c2pa_primitives::TransformContext,
+            ) -> ::core::result::Result<c2pa_primitives::C2pa<#actual_output_type, c2pa_primitives::Verified>, c2pa_primitives::TransformError> {
+                #payload_extraction
+
+                // Both inputs become ingredients - this creates the DAG
+                c2pa_primitives::transform_helper::build_multi_input_result(
+                    __c2pa_out_payload,
+                    vec![
+                        c2pa_primitives::IngredientRef::from_verified(#a, #a_rel),
+                        c2pa_primitives::IngredientRef::from_verified(#b, #b_rel),
+                    ],
                     #transform_name,
-                    #relationship,
-                    param_commits,
+                    Vec::new(),
+                    None,
                     ctx,
                 )
+            }
+        }
+
+        // Generated wrapper function (uses thread-local ctx)
+        #fn_vis fn #wrapper_name(
+            #a: &c2pa_primitives::C2pa<#a_type, c2pa_primitives::Verified>,
+            #b: &c2pa_primitives::C2pa<#b_type, c2pa_primitives::Verified>,
+        ) -> ::core::result::Result<c2pa_primitives::C2pa<#actual_output_type, c2pa_primitives::Verified>, c2pa_primitives::TransformError> {
+            c2pa_primitives::with_ctx(|ctx| {
+                c2pa_primitives::C2paComposite::compose(&#struct_name, #a, #b, ctx)
             })
         }
     };
 
     Ok(output)
 }
+
+// ============================================================================
+// #[derive(C2paBindable)] - Field-wise content binding
+// ============================================================================
+
+/// Derive `C2paBindable` by hashing every field through its own
+/// `C2paBindable` impl.
+///
+/// Field hashes are combined with `ContentHash::from_fields`, keyed by field
+/// name (or index for tuple structs), so reordering the struct definition
+/// does not change the hash. Fields are hashed with the same algorithm as
+/// the whole.
+///
+/// - `#[c2pa(skip)]` on a field excludes it from the hash.
+/// - `#[c2pa(media_type = "...")]` on the type sets `media_type()`.
+#[proc_macro_derive(C2paBindable, attributes(c2pa))]
+pub fn derive_c2pa_bindable(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    match generate_bindable(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Options from `#[c2pa(...)]` on a type or field.
+#[derive(Default)]
+struct BindableAttr {
+    skip: bool,
+    media_type: Option<LitStr>,
+}
+
+fn parse_bindable_attrs(attrs: &[syn::Attribute]) -> Result<BindableAttr> {
+    let mut parsed = BindableAttr::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("c2pa")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                parsed.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("media_type") {
+                parsed.media_type = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown c2pa attribute"))
+            }
+        })?;
+    }
+    Ok(parsed)
+}
+
+fn generate_bindable(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+
+    let type_attr = parse_bindable_attrs(&input.attrs)?;
+    if type_attr.skip {
+        return Err(Error::new(name.span(), "`skip` is only valid on fields"));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                name.span(),
+                "C2paBindable can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut field_hashes = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let field_attr = parse_bindable_attrs(&field.attrs)?;
+        if let Some(media_type) = &field_attr.media_type {
+            return Err(Error::new(
+                media_type.span(),
+                "`media_type` is only valid on the type",
+            ));
+        }
+        if field_attr.skip {
+            continue;
+        }
+
+        let (key, member) = match &field.ident {
+            Some(ident) => (ident.to_string(), quote! { #ident }),
+            None => {
+                let index = syn::Index::from(index);
+                (index.index.to_string(), quote! { #index })
+            }
+        };
+        field_hashes.push(quote! {
+            (#key, c2pa_primitives::C2paBindable::content_hash_with(&self.#member, alg))
+        });
+    }
+
+    let media_type = type_attr.media_type.map(|media_type| {
+        quote! {
+            fn media_type(&self) -> &str {
+                #media_type
+            }
+        }
+    });
+
+    // Every type parameter must itself be bindable
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(c2pa_primitives::C2paBindable));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics c2pa_primitives::C2paBindable for #name #ty_generics #where_clause {
+            fn content_hash_with(
+                &self,
+                alg: c2pa_primitives::HashAlg,
+            ) -> c2pa_primitives::ContentHash {
+                c2pa_primitives::ContentHash::from_fields(alg, &[#(#field_hashes),*])
+            }
+
+            #media_type
+        }
+    })
+}
+
+// ============================================================================
+// #[derive(C2paCommit)] - Canonical parameter encoding
+// ============================================================================
+
+/// Derive `C2paCommit` by encoding every field through its own
+/// `C2paCommit` impl.
+///
+/// Fields are keyed by name (or index for tuple fields) with
+/// `c2pa_primitives::commit_fields`; enum variants are additionally tagged
+/// by name with `c2pa_primitives::commit_variant`.
+///
+/// - `#[c2pa_commit(skip)]` on a field excludes it from the encoding.
+///
+/// `#[c2pa(...)]` attributes belong to `C2paBindable` and are ignored, so
+/// a type can derive both and skip a field in only one of them.
+#[proc_macro_derive(C2paCommit, attributes(c2pa_commit))]
+pub fn derive_c2pa_commit(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    match generate_commit(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Whether `#[c2pa_commit(skip)]` is among `attrs`.
+fn parse_commit_skip(attrs: &[syn::Attribute]) -> Result<bool> {
+    let mut skip = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("c2pa_commit")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown c2pa_commit attribute"))
+            }
+        })?;
+    }
+    Ok(skip)
+}
+
+/// A field included in a derived `C2paCommit` encoding.
+struct CommittedField {
+    /// Key in the encoded map: field name, or index for tuple fields.
+    key: String,
+    member: syn::Member,
+    /// Local the field is bound to when destructuring.
+    binding: Ident,
+}
+
+fn committed_fields(fields: &syn::Fields) -> Result<Vec<CommittedField>> {
+    let mut committed = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        if parse_commit_skip(&field.attrs)? {
+            continue;
+        }
+
+        let (key, member) = match &field.ident {
+            Some(ident) => (ident.to_string(), syn::Member::Named(ident.clone())),
+            None => (index.to_string(), syn::Member::Unnamed(syn::Index::from(index))),
+        };
+        committed.push(CommittedField {
+            key,
+            member,
+            binding: format_ident!("__field{}", index),
+        });
+    }
+    Ok(committed)
+}
+
+/// Destructuring pattern binding every committed field.
+fn fields_pattern(
+    path: TokenStream2,
+    fields: &syn::Fields,
+    committed: &[CommittedField],
+) -> TokenStream2 {
+    if let syn::Fields::Unit = fields {
+        return path;
+    }
+    let bindings = committed.iter().map(|field| {
+        let (member, binding) = (&field.member, &field.binding);
+        quote! { #member: #binding }
+    });
+    quote! { #path { #(#bindings,)* .. } }
+}
+
+fn fields_encoding(committed: &[CommittedField]) -> TokenStream2 {
+    let entries = committed.iter().map(|field| {
+        let (key, binding) = (&field.key, &field.binding);
+        quote! { (#key, c2pa_primitives::C2paCommit::commit_bytes(#binding)) }
+    });
+    quote! { &[#(#entries),*] }
+}
+
+fn generate_commit(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+
+    if let Some(attr) = input.attrs.iter().find(|attr| attr.path().is_ident("c2pa_commit")) {
+        return Err(Error::new(attr.span(), "`skip` is only valid on fields"));
+    }
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let committed = committed_fields(&data.fields)?;
+            let pattern = fields_pattern(quote! { Self }, &data.fields, &committed);
+            let encoding = fields_encoding(&committed);
+            quote! {
+                let #pattern = self;
+                c2pa_primitives::commit_fields(#encoding)
+            }
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let attr = variant.attrs.iter().find(|attr| attr.path().is_ident("c2pa_commit"));
+                if let Some(attr) = attr {
+                    return Err(Error::new(attr.span(), "`skip` is only valid on fields"));
+                }
+                let ident = &variant.ident;
+                let key = ident.to_string();
+                let committed = committed_fields(&variant.fields)?;
+                let pattern = fields_pattern(quote! { Self::#ident }, &variant.fields, &committed);
+                let encoding = fields_encoding(&committed);
+                arms.push(quote! {
+                    #pattern => c2pa_primitives::commit_variant(#key, #encoding),
+                });
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new(
+                name.span(),
+                "C2paCommit can only be derived for structs and enums",
+            ));
+        }
+    };
+
+    // Every type parameter must itself be committable
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(c2pa_primitives::C2paCommit));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics c2pa_primitives::C2paCommit for #name #ty_generics #where_clause {
+            fn commit_bytes(&self) -> ::std::vec::Vec<u8> {
+                #body
+            }
+        }
+    })
+}
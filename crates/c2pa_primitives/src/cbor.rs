@@ -0,0 +1,338 @@
+//! Minimal deterministic CBOR (RFC 8949) encoding and decoding.
+//!
+//! Only the subset needed for claims and manifests is supported. Encoding
+//! follows the core deterministic rules (RFC 8949 §4.2.1): shortest-form
+//! integers and lengths, definite lengths only, and map keys sorted by their
+//! encoded bytes. Equal values therefore always produce identical bytes.
+//!
+//! Decoding accepts the same subset and rejects anything else
+//! (indefinite lengths, floats, simple values other than `null`, trailing
+//! bytes).
+
+use std::fmt;
+
+/// A CBOR data item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// Integer in the range `-2^64 ..= 2^64 - 1`.
+    Int(i128),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Value>),
+    /// Map entries; order is irrelevant, encoding sorts the keys.
+    Map(Vec<(Value, Value)>),
+    /// Tagged data item (e.g. tag 18 for COSE_Sign1).
+    Tag(u64, Box<Value>),
+    Null,
+}
+
+impl Value {
+    /// Convenience for building a map with text keys.
+    pub fn map<const N: usize>(entries: [(&str, Value); N]) -> Self {
+        Value::Map(
+            entries
+                .into_iter()
+                .map(|(k, v)| (Value::Text(k.into()), v))
+                .collect(),
+        )
+    }
+
+    pub fn text(s: impl Into<String>) -> Self {
+        Value::Text(s.into())
+    }
+
+    pub fn bytes(b: impl Into<Vec<u8>>) -> Self {
+        Value::Bytes(b.into())
+    }
+
+    pub fn uint(n: u64) -> Self {
+        Value::Int(n as i128)
+    }
+
+    /// Look up a text key in a map.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| matches!(k, Value::Text(t) if t == key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Look up an integer key in a map.
+    pub fn get_int(&self, key: i128) -> Option<&Value> {
+        match self {
+            Value::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| matches!(k, Value::Int(n) if *n == key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Value::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_uint(&self) -> Option<u64> {
+        match self {
+            Value::Int(n) => u64::try_from(*n).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Encode to deterministic CBOR bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Value::Int(n) if *n >= 0 => write_head(out, 0, *n as u64),
+            Value::Int(n) => write_head(out, 1, (-1 - *n) as u64),
+            Value::Bytes(b) => {
+                write_head(out, 2, b.len() as u64);
+                out.extend_from_slice(b);
+            }
+            Value::Text(s) => {
+                write_head(out, 3, s.len() as u64);
+                out.extend_from_slice(s.as_bytes());
+            }
+            Value::Array(items) => {
+                write_head(out, 4, items.len() as u64);
+                for item in items {
+                    item.encode(out);
+                }
+            }
+            Value::Map(entries) => {
+                let mut encoded: Vec<(Vec<u8>, Vec<u8>)> = entries
+                    .iter()
+                    .map(|(k, v)| (k.to_bytes(), v.to_bytes()))
+                    .collect();
+                encoded.sort_by(|a, b| a.0.cmp(&b.0));
+                write_head(out, 5, encoded.len() as u64);
+                for (k, v) in encoded {
+                    out.extend_from_slice(&k);
+                    out.extend_from_slice(&v);
+                }
+            }
+            Value::Tag(tag, item) => {
+                write_head(out, 6, *tag);
+                item.encode(out);
+            }
+            Value::Null => out.push(NULL),
+        }
+    }
+}
+
+/// Error produced when decoding malformed or unsupported CBOR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError(pub &'static str);
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Value {
+    /// Decode exactly one data item from `bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader { bytes, pos: 0 };
+        let value = reader.read_value(0)?;
+        if reader.pos != bytes.len() {
+            return Err(DecodeError("trailing bytes"));
+        }
+        Ok(value)
+    }
+}
+
+/// Nesting limit guarding against stack exhaustion on hostile input.
+const MAX_DEPTH: usize = 64;
+
+/// Encoding of `null` (major type 7, simple value 22).
+const NULL: u8 = 0xf6;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], DecodeError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(DecodeError("unexpected end of input"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_head(&mut self) -> Result<(u8, u64), DecodeError> {
+        let initial = self.take(1)?[0];
+        let major = initial >> 5;
+        let arg = match initial & 0x1f {
+            n @ 0..=23 => n as u64,
+            24 => self.take(1)?[0] as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            _ => return Err(DecodeError("unsupported additional information")),
+        };
+        Ok((major, arg))
+    }
+
+    fn read_len(&mut self, arg: u64) -> Result<usize, DecodeError> {
+        let len = usize::try_from(arg).map_err(|_| DecodeError("length too large"))?;
+        // Every item takes at least one byte, so this bounds allocations
+        if len > self.bytes.len() - self.pos {
+            return Err(DecodeError("unexpected end of input"));
+        }
+        Ok(len)
+    }
+
+    fn read_value(&mut self, depth: usize) -> Result<Value, DecodeError> {
+        if depth > MAX_DEPTH {
+            return Err(DecodeError("nesting too deep"));
+        }
+        if self.bytes.get(self.pos) == Some(&NULL) {
+            self.pos += 1;
+            return Ok(Value::Null);
+        }
+        let (major, arg) = self.read_head()?;
+        match major {
+            0 => Ok(Value::Int(arg as i128)),
+            1 => Ok(Value::Int(-1 - arg as i128)),
+            2 => {
+                let len = self.read_len(arg)?;
+                Ok(Value::Bytes(self.take(len)?.to_vec()))
+            }
+            3 => {
+                let len = self.read_len(arg)?;
+                let text = std::str::from_utf8(self.take(len)?)
+                    .map_err(|_| DecodeError("invalid UTF-8 in text string"))?;
+                Ok(Value::Text(text.to_string()))
+            }
+            4 => {
+                let len = self.read_len(arg)?;
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(self.read_value(depth + 1)?);
+                }
+                Ok(Value::Array(items))
+            }
+            5 => {
+                let len = self.read_len(arg)?;
+                let mut entries = Vec::with_capacity(len);
+                for _ in 0..len {
+                    let key = self.read_value(depth + 1)?;
+                    let value = self.read_value(depth + 1)?;
+                    entries.push((key, value));
+                }
+                Ok(Value::Map(entries))
+            }
+            6 => Ok(Value::Tag(arg, Box::new(self.read_value(depth + 1)?))),
+            _ => Err(DecodeError("unsupported major type")),
+        }
+    }
+}
+
+/// Write a major type and argument in shortest form.
+fn write_head(out: &mut Vec<u8>, major: u8, arg: u64) {
+    let major = major << 5;
+    match arg {
+        0..=23 => out.push(major | arg as u8),
+        24..=0xff => {
+            out.push(major | 24);
+            out.push(arg as u8);
+        }
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(arg as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(arg as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&arg.to_be_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc8949_vectors() {
+        assert_eq!(Value::Int(0).to_bytes(), [0x00]);
+        assert_eq!(Value::Int(23).to_bytes(), [0x17]);
+        assert_eq!(Value::Int(24).to_bytes(), [0x18, 0x18]);
+        assert_eq!(Value::Int(1_000_000).to_bytes(), [0x1a, 0x00, 0x0f, 0x42, 0x40]);
+        assert_eq!(Value::Int(-1).to_bytes(), [0x20]);
+        assert_eq!(Value::Int(-1000).to_bytes(), [0x39, 0x03, 0xe7]);
+        assert_eq!(Value::text("IETF").to_bytes(), b"\x64IETF");
+        assert_eq!(Value::bytes(vec![1, 2, 3, 4]).to_bytes(), [0x44, 1, 2, 3, 4]);
+        assert_eq!(Value::Null.to_bytes(), [0xf6]);
+        assert_eq!(
+            Value::Tag(1, Box::new(Value::Int(1_363_896_240))).to_bytes(),
+            [0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0]
+        );
+    }
+
+    #[test]
+    fn test_map_keys_sorted_deterministically() {
+        let a = Value::map([("b", Value::Int(1)), ("a", Value::Int(2))]);
+        let b = Value::map([("a", Value::Int(2)), ("b", Value::Int(1))]);
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        assert_eq!(a.to_bytes(), [0xa2, 0x61, b'a', 0x02, 0x61, b'b', 0x01]);
+    }
+
+    #[test]
+    fn test_decode_roundtrip() {
+        let value = Value::map([
+            ("n", Value::Int(-500)),
+            ("big", Value::uint(u64::MAX)),
+            ("b", Value::bytes(vec![0; 300])),
+            ("list", Value::Array(vec![Value::text("x"), Value::Int(1)])),
+            ("tagged", Value::Tag(18, Box::new(Value::Array(vec![Value::Null])))),
+        ]);
+        let decoded = Value::from_bytes(&value.to_bytes()).unwrap();
+        assert_eq!(decoded.to_bytes(), value.to_bytes());
+        assert_eq!(decoded.get("big").and_then(Value::as_uint), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed_input() {
+        assert!(Value::from_bytes(&[]).is_err());
+        assert!(Value::from_bytes(&[0x00, 0x00]).is_err()); // trailing bytes
+        assert!(Value::from_bytes(&[0x5a, 0xff, 0xff, 0xff, 0xff]).is_err()); // truncated
+        assert!(Value::from_bytes(&[0x9f]).is_err()); // indefinite length
+        assert!(Value::from_bytes(&[0xf5]).is_err()); // simple values other than null
+        assert!(Value::from_bytes(&[0xf8, 0x16]).is_err()); // non-shortest null
+    }
+}
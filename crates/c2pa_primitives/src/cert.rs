@@ -0,0 +1,503 @@
+//! X.509-style signing certificates and trust anchors.
+//!
+//! Certificates carry the fields chain validation needs from X.509 —
+//! names, validity period, key usage and basic constraints — encoded as
+//! deterministic CBOR rather than DER:
+//!
+//! ```text
+//! certificate = { "tbs": tbs, "sig_alg": tstr, "signature": bstr }
+//!
+//! tbs = {
+//!   "version":    1,
+//!   "serial":     uint,
+//!   "subject":    tstr,
+//!   "issuer":     tstr,
+//!   "not_before": uint,            ; seconds since the Unix epoch
+//!   "not_after":  uint,
+//!   "key_usage":  [* tstr],        ; "digitalSignature" / "keyCertSign"
+//!   ? "ca":       { ? "path_len": uint },
+//!   "alg":        tstr,
+//!   "public_key": bstr,
+//! }
+//! ```
+//!
+//! A [`ClaimSignature`](crate::ClaimSignature) carries its chain leaf first.
+//! [`TrustStore::validate`] checks that chain and reports whether it ends
+//! at one of the store's anchors.
+
+use crate::cbor::Value;
+use crate::claim::{self, array, bytes, field, text};
+use crate::signers::verifier_for;
+use crate::{key_id_for, Signer, SigningAlg, StatusCode, TransformError, ValidationStatus};
+
+/// Version of the certificate encoding.
+const CERTIFICATE_FORMAT_VERSION: u64 = 1;
+
+/// What a certificate's key may be used for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyUsage {
+    /// May sign claims.
+    pub digital_signature: bool,
+    /// May issue certificates.
+    pub key_cert_sign: bool,
+}
+
+/// A signing certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    pub serial: u64,
+    pub subject: String,
+    pub issuer: String,
+    /// Start of the validity period, in seconds since the Unix epoch.
+    pub not_before: u64,
+    /// End of the validity period (inclusive).
+    pub not_after: u64,
+    pub key_usage: KeyUsage,
+    /// `Some` for CA certificates, with an optional limit on the number
+    /// of intermediate CAs below this one.
+    pub ca: Option<Option<u64>>,
+    /// Algorithm of the subject key.
+    pub algorithm: SigningAlg,
+    /// Raw subject public key.
+    pub public_key: Vec<u8>,
+    /// Algorithm of the issuer's signature.
+    pub signature_algorithm: SigningAlg,
+    /// Issuer's signature over [`tbs_bytes`](Self::tbs_bytes).
+    pub signature: Vec<u8>,
+}
+
+impl Certificate {
+    /// The bytes the issuer signs.
+    pub fn tbs_bytes(&self) -> Vec<u8> {
+        self.tbs_value().to_bytes()
+    }
+
+    /// Identifier of the subject key, matching [`Signer::key_id`].
+    pub fn key_id(&self) -> Vec<u8> {
+        key_id_for(&self.public_key)
+    }
+
+    /// True if `time` is within the validity period.
+    pub fn is_valid_at(&self, time: u64) -> bool {
+        self.not_before <= time && time <= self.not_after
+    }
+
+    /// Check that `issuer` signed this certificate.
+    pub fn verify_issued_by(&self, issuer: &Certificate) -> Result<(), TransformError> {
+        if self.issuer != issuer.subject {
+            return Err(TransformError::Verification(format!(
+                "`{}` is issued by `{}`, not `{}`",
+                self.subject, self.issuer, issuer.subject
+            )));
+        }
+        if self.signature_algorithm != issuer.algorithm {
+            return Err(TransformError::Verification(format!(
+                "`{}` is signed with {}, but `{}` holds a {} key",
+                self.subject,
+                self.signature_algorithm.as_str(),
+                issuer.subject,
+                issuer.algorithm.as_str()
+            )));
+        }
+        verifier_for(issuer.algorithm, &issuer.public_key)?
+            .verify(&self.tbs_bytes(), &self.signature)
+    }
+
+    /// Encode to the certificate format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Value::map([
+            ("tbs", self.tbs_value()),
+            ("sig_alg", Value::text(self.signature_algorithm.as_str())),
+            ("signature", Value::bytes(self.signature.clone())),
+        ])
+        .to_bytes()
+    }
+
+    /// Parse the certificate format.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, TransformError> {
+        let value = Value::from_bytes(data)
+            .map_err(|e| TransformError::Manifest(format!("invalid certificate: {}", e)))?;
+        let tbs = field(&value, "tbs")?;
+
+        let version = claim::uint(tbs, "version")?;
+        if version != CERTIFICATE_FORMAT_VERSION {
+            return Err(TransformError::Manifest(format!(
+                "unsupported certificate version {}",
+                version
+            )));
+        }
+
+        let mut key_usage = KeyUsage::default();
+        for usage in array(tbs, "key_usage")? {
+            match usage.as_text() {
+                Some("digitalSignature") => key_usage.digital_signature = true,
+                Some("keyCertSign") => key_usage.key_cert_sign = true,
+                _ => return Err(claim::malformed("key_usage")),
+            }
+        }
+        let ca = match tbs.get("ca") {
+            Some(ca) => Some(match ca.get("path_len") {
+                Some(path_len) => {
+                    Some(path_len.as_uint().ok_or_else(|| claim::malformed("path_len"))?)
+                }
+                None => None,
+            }),
+            None => None,
+        };
+
+        Ok(Self {
+            serial: claim::uint(tbs, "serial")?,
+            subject: text(tbs, "subject")?.to_string(),
+            issuer: text(tbs, "issuer")?.to_string(),
+            not_before: claim::uint(tbs, "not_before")?,
+            not_after: claim::uint(tbs, "not_after")?,
+            key_usage,
+            ca,
+            algorithm: text(tbs, "alg")?.parse()?,
+            public_key: bytes(tbs, "public_key")?.to_vec(),
+            signature_algorithm: text(&value, "sig_alg")?.parse()?,
+            signature: bytes(&value, "signature")?.to_vec(),
+        })
+    }
+
+    fn tbs_value(&self) -> Value {
+        let mut usages = Vec::new();
+        if self.key_usage.digital_signature {
+            usages.push(Value::text("digitalSignature"));
+        }
+        if self.key_usage.key_cert_sign {
+            usages.push(Value::text("keyCertSign"));
+        }
+
+        let mut entries = vec![
+            (Value::text("version"), Value::uint(CERTIFICATE_FORMAT_VERSION)),
+            (Value::text("serial"), Value::uint(self.serial)),
+            (Value::text("subject"), Value::text(&self.subject)),
+            (Value::text("issuer"), Value::text(&self.issuer)),
+            (Value::text("not_before"), Value::uint(self.not_before)),
+            (Value::text("not_after"), Value::uint(self.not_after)),
+            (Value::text("key_usage"), Value::Array(usages)),
+            (Value::text("alg"), Value::text(self.algorithm.as_str())),
+            (Value::text("public_key"), Value::bytes(self.public_key.clone())),
+        ];
+        if let Some(path_len) = self.ca {
+            let constraints = match path_len {
+                Some(path_len) => Value::map([("path_len", Value::uint(path_len))]),
+                None => Value::Map(Vec::new()),
+            };
+            entries.push((Value::text("ca"), constraints));
+        }
+        Value::Map(entries)
+    }
+}
+
+/// Builder for issuing certificates.
+///
+/// # Example
+///
+/// ```
+/// use c2pa_primitives::*;
+///
+/// let root_key = Ed25519Signer::from_raw(&[1u8; 32]).unwrap();
+/// let root = CertificateBuilder::new("Example Root CA")
+///     .ca(None)
+///     .public_key(SigningAlg::Ed25519, &root_key.verifier().public_key_bytes())
+///     .self_signed(&root_key)
+///     .unwrap();
+///
+/// let leaf_key = Ed25519Signer::from_raw(&[2u8; 32]).unwrap();
+/// let leaf = CertificateBuilder::new("Example Generator")
+///     .public_key(SigningAlg::Ed25519, &leaf_key.verifier().public_key_bytes())
+///     .issue(&root, &root_key)
+///     .unwrap();
+///
+/// let store = TrustStore::new().add_anchor(root);
+/// assert!(store.validate(&[leaf.to_bytes()], 0).is_ok());
+/// ```
+pub struct CertificateBuilder {
+    subject: String,
+    serial: u64,
+    not_before: u64,
+    not_after: u64,
+    ca: Option<Option<u64>>,
+    public_key: Option<(SigningAlg, Vec<u8>)>,
+}
+
+impl CertificateBuilder {
+    /// Start a certificate for `subject`, valid for all time.
+    pub fn new(subject: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            serial: 1,
+            not_before: 0,
+            not_after: u64::MAX,
+            ca: None,
+            public_key: None,
+        }
+    }
+
+    pub fn serial(mut self, serial: u64) -> Self {
+        self.serial = serial;
+        self
+    }
+
+    /// Set the validity period, in seconds since the Unix epoch.
+    pub fn validity(mut self, not_before: u64, not_after: u64) -> Self {
+        self.not_before = not_before;
+        self.not_after = not_after;
+        self
+    }
+
+    /// Make this a CA certificate, allowed to issue certificates.
+    ///
+    /// `path_len` limits the number of intermediate CAs below it.
+    pub fn ca(mut self, path_len: Option<u64>) -> Self {
+        self.ca = Some(path_len);
+        self
+    }
+
+    /// The subject key the certificate vouches for.
+    pub fn public_key(mut self, algorithm: SigningAlg, public_key: &[u8]) -> Self {
+        self.public_key = Some((algorithm, public_key.to_vec()));
+        self
+    }
+
+    /// Sign with the subject's own key.
+    pub fn self_signed(self, key: &dyn Signer) -> Result<Certificate, TransformError> {
+        let issuer = self.subject.clone();
+        self.sign(issuer, key)
+    }
+
+    /// Sign with the key of the CA certificate `issuer`.
+    pub fn issue(
+        self,
+        issuer: &Certificate,
+        issuer_key: &dyn Signer,
+    ) -> Result<Certificate, TransformError> {
+        if issuer_key.key_id() != issuer.key_id() {
+            return Err(TransformError::Signing(format!(
+                "key does not belong to issuer `{}`",
+                issuer.subject
+            )));
+        }
+        self.sign(issuer.subject.clone(), issuer_key)
+    }
+
+    fn sign(self, issuer: String, key: &dyn Signer) -> Result<Certificate, TransformError> {
+        let (algorithm, public_key) = self
+            .public_key
+            .ok_or_else(|| TransformError::Signing("certificate has no public key".into()))?;
+        let mut certificate = Certificate {
+            serial: self.serial,
+            subject: self.subject,
+            issuer,
+            not_before: self.not_before,
+            not_after: self.not_after,
+            key_usage: KeyUsage {
+                digital_signature: true,
+                key_cert_sign: self.ca.is_some(),
+            },
+            ca: self.ca,
+            algorithm,
+            public_key,
+            signature_algorithm: key.algorithm(),
+            signature: Vec::new(),
+        };
+        certificate.signature = key.sign(&certificate.tbs_bytes())?;
+        Ok(certificate)
+    }
+}
+
+/// Anchor certificates that signing chains must lead to.
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    anchors: Vec<Certificate>,
+}
+
+impl TrustStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust chains ending at (or issued by) `anchor`.
+    pub fn add_anchor(mut self, anchor: Certificate) -> Self {
+        self.anchors.push(anchor);
+        self
+    }
+
+    pub fn anchors(&self) -> &[Certificate] {
+        &self.anchors
+    }
+
+    /// Validate a leaf-first certificate chain at `time`.
+    ///
+    /// Checks every certificate's validity period, that each one is signed
+    /// by the next, and the key usage and basic constraints of every
+    /// issuer. `Ok` means the chain is valid and trusted; an
+    /// [`UntrustedSigner`](StatusCode::UntrustedSigner) error means it is
+    /// valid but does not lead to an anchor.
+    pub fn validate(&self, chain: &[Vec<u8>], time: u64) -> Result<(), ValidationStatus> {
+        let certificates = chain
+            .iter()
+            .map(|der| Certificate::from_bytes(der))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| invalid(e.to_string()))?;
+        let Some(leaf) = certificates.first() else {
+            return Err(invalid("empty certificate chain"));
+        };
+        if !leaf.key_usage.digital_signature {
+            return Err(invalid(format!("`{}` may not sign claims", leaf.subject)));
+        }
+
+        for (depth, certificate) in certificates.iter().enumerate() {
+            check_validity(certificate, time)?;
+            if depth > 0 {
+                check_issuer(certificate, depth - 1)?;
+                certificates[depth - 1]
+                    .verify_issued_by(certificate)
+                    .map_err(|e| invalid(e.to_string()))?;
+            }
+        }
+
+        // The chain may end at an anchor, or at a certificate an anchor issued
+        let top = certificates.last().unwrap();
+        if self.anchors.contains(top) {
+            return Ok(());
+        }
+        let intermediates = certificates.len() - 1;
+        for anchor in self.anchors.iter().filter(|anchor| anchor.subject == top.issuer) {
+            if top.verify_issued_by(anchor).is_ok() {
+                check_validity(anchor, time)?;
+                check_issuer(anchor, intermediates)?;
+                return Ok(());
+            }
+        }
+
+        Err(ValidationStatus::new(
+            StatusCode::UntrustedSigner,
+            format!("`{}` does not chain to a trust anchor", top.issuer),
+        ))
+    }
+}
+
+fn invalid(explanation: impl Into<String>) -> ValidationStatus {
+    ValidationStatus::new(StatusCode::CertificateInvalid, explanation)
+}
+
+fn check_validity(certificate: &Certificate, time: u64) -> Result<(), ValidationStatus> {
+    if certificate.is_valid_at(time) {
+        Ok(())
+    } else {
+        Err(ValidationStatus::new(
+            StatusCode::CertificateExpired,
+            format!("`{}` is not valid at {}", certificate.subject, time),
+        ))
+    }
+}
+
+/// An issuer must be a CA allowed to sign certificates, with room for the
+/// `intermediates` CAs below it.
+fn check_issuer(issuer: &Certificate, intermediates: usize) -> Result<(), ValidationStatus> {
+    let Some(path_len) = issuer.ca else {
+        return Err(invalid(format!("`{}` is not a CA", issuer.subject)));
+    };
+    if !issuer.key_usage.key_cert_sign {
+        return Err(invalid(format!("`{}` may not issue certificates", issuer.subject)));
+    }
+    if path_len.is_some_and(|max| intermediates as u64 > max) {
+        return Err(invalid(format!("path length of `{}` exceeded", issuer.subject)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_signer;
+    use crate::Ed25519Signer;
+
+    fn builder(subject: &str, key: &Ed25519Signer) -> CertificateBuilder {
+        CertificateBuilder::new(subject)
+            .public_key(SigningAlg::Ed25519, &key.verifier().public_key_bytes())
+    }
+
+    /// root (1) -> intermediate (2, path_len 0) -> leaf (3)
+    fn chain() -> (Certificate, Certificate, Certificate) {
+        let (root_key, intermediate_key) = (test_signer(1), test_signer(2));
+        let root = builder("root", &root_key).ca(None).self_signed(&root_key).unwrap();
+        let intermediate = builder("intermediate", &intermediate_key)
+            .ca(Some(0))
+            .validity(100, 200)
+            .issue(&root, &root_key)
+            .unwrap();
+        let leaf = builder("leaf", &test_signer(3))
+            .issue(&intermediate, &intermediate_key)
+            .unwrap();
+        (root, intermediate, leaf)
+    }
+
+    fn code(result: Result<(), ValidationStatus>) -> Option<StatusCode> {
+        result.err().map(|status| status.code)
+    }
+
+    #[test]
+    fn test_encoding_roundtrip() {
+        let (_, intermediate, _) = chain();
+        assert_eq!(Certificate::from_bytes(&intermediate.to_bytes()).unwrap(), intermediate);
+    }
+
+    #[test]
+    fn test_trusted_and_untrusted_chains() {
+        let (root, intermediate, leaf) = chain();
+        let store = TrustStore::new().add_anchor(root.clone());
+
+        let with_root = [leaf.to_bytes(), intermediate.to_bytes(), root.to_bytes()];
+        assert!(store.validate(&with_root, 150).is_ok());
+        assert!(store.validate(&with_root[..2], 150).is_ok());
+
+        // Structurally valid, but no anchor
+        let impostor_key = test_signer(9);
+        let impostor = builder("root", &impostor_key).ca(None).self_signed(&impostor_key).unwrap();
+        let other = TrustStore::new().add_anchor(impostor);
+        assert_eq!(code(other.validate(&with_root, 150)), Some(StatusCode::UntrustedSigner));
+    }
+
+    #[test]
+    fn test_validity_period() {
+        let (root, intermediate, leaf) = chain();
+        let store = TrustStore::new().add_anchor(root);
+        let chain = [leaf.to_bytes(), intermediate.to_bytes()];
+        assert_eq!(code(store.validate(&chain, 99)), Some(StatusCode::CertificateExpired));
+        assert_eq!(code(store.validate(&chain, 201)), Some(StatusCode::CertificateExpired));
+    }
+
+    #[test]
+    fn test_basic_constraints_and_path_length() {
+        let (root, intermediate, leaf) = chain();
+        let store = TrustStore::new().add_anchor(root);
+
+        // A leaf cannot issue certificates
+        let below_leaf = builder("below", &test_signer(4)).issue(&leaf, &test_signer(3)).unwrap();
+        let chain = [below_leaf.to_bytes(), leaf.to_bytes(), intermediate.to_bytes()];
+        assert_eq!(code(store.validate(&chain, 150)), Some(StatusCode::CertificateInvalid));
+
+        // path_len 0 forbids a further CA below the intermediate
+        let sub_key = test_signer(5);
+        let sub_ca = builder("sub", &sub_key)
+            .ca(None)
+            .issue(&intermediate, &test_signer(2))
+            .unwrap();
+        let sub_leaf = builder("leaf", &test_signer(6)).issue(&sub_ca, &sub_key).unwrap();
+        let chain = [sub_leaf.to_bytes(), sub_ca.to_bytes(), intermediate.to_bytes()];
+        assert_eq!(code(store.validate(&chain, 150)), Some(StatusCode::CertificateInvalid));
+    }
+
+    #[test]
+    fn test_forged_signature_is_invalid() {
+        let (root, intermediate, mut leaf) = chain();
+        leaf.subject = "someone else".into();
+        let store = TrustStore::new().add_anchor(root);
+        let chain = [leaf.to_bytes(), intermediate.to_bytes()];
+        assert_eq!(code(store.validate(&chain, 150)), Some(StatusCode::CertificateInvalid));
+    }
+}
@@ -0,0 +1,234 @@
+//! Whole-graph provenance verification.
+//!
+//! [`ChainVerifier`] walks every ingredient reachable from a head manifest,
+//! resolving each [`IngredientRef`](crate::IngredientRef) by claim hash and
+//! checking every node it reaches. Results are returned as a
+//! [`ValidationReport`] with one entry per manifest.
+
+use std::collections::HashMap;
+
+use crate::{
+    check_claim, ClaimHash, ManifestStatus, Provenance, StatusCode, TrustedKeys,
+    ValidationReport, ValidationStatus,
+};
+
+/// Verifies a provenance DAG assembled from a set of manifests.
+///
+/// # Example
+///
+/// ```
+/// use c2pa_primitives::*;
+///
+/// let signer = Ed25519Signer::from_raw(&[1u8; 32]).unwrap();
+/// let source = C2paBuilder::new(1u32).sign(&signer).unwrap();
+/// let output = C2paBuilder::new(2u32)
+///     .add_ingredient(&source, IngredientRelation::ParentOf)
+///     .sign(&signer)
+///     .unwrap();
+///
+/// let trusted = TrustedKeys::new().add_key(signer.verifier());
+/// let report = ChainVerifier::new(&trusted)
+///     .add_manifest(source.provenance().clone())
+///     .add_manifest(output.provenance().clone())
+///     .verify(&output.provenance().claim_hash);
+/// assert!(report.is_valid());
+/// assert_eq!(report.manifests.len(), 2);
+/// ```
+pub struct ChainVerifier<'a> {
+    trusted: &'a TrustedKeys,
+    manifests: HashMap<ClaimHash, Provenance>,
+}
+
+impl<'a> ChainVerifier<'a> {
+    pub fn new(trusted: &'a TrustedKeys) -> Self {
+        Self {
+            trusted,
+            manifests: HashMap::new(),
+        }
+    }
+
+    /// Make a manifest available for ingredient resolution.
+    pub fn add_manifest(mut self, provenance: Provenance) -> Self {
+        self.manifests.insert(provenance.claim_hash.clone(), provenance);
+        self
+    }
+
+    /// Verify `head` and every manifest reachable through its ingredients.
+    ///
+    /// Manifests are reported in depth-first order, starting with `head`.
+    pub fn verify(&self, head: &ClaimHash) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let mut index: HashMap<&ClaimHash, usize> = HashMap::new();
+        let mut done: HashMap<&ClaimHash, bool> = HashMap::new();
+
+        let Some((head, _)) = self.manifests.get_key_value(head) else {
+            report.manifests.push(ManifestStatus {
+                manifest_id: None,
+                claim_hash: head.clone(),
+                statuses: vec![ValidationStatus::new(
+                    StatusCode::ManifestMissing,
+                    "head manifest was not supplied",
+                )],
+            });
+            return report;
+        };
+
+        // Explicit stack of (node, next ingredient) so deep chains cannot
+        // overflow the call stack.
+        let mut stack = vec![(head, 0usize)];
+        index.insert(head, self.check_node(head, &mut report));
+        done.insert(head, false);
+
+        while let Some((key, next)) = stack.last_mut() {
+            let key = *key;
+            let node = &self.manifests[key];
+
+            let Some(ingredient) = node.ingredients.get(*next) else {
+                done.insert(key, true);
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+
+            let statuses = &mut report.manifests[index[key]].statuses;
+            let Some((parent_key, parent)) = self.manifests.get_key_value(&ingredient.claim_hash)
+            else {
+                statuses.push(ValidationStatus::for_ingredient(
+                    StatusCode::IngredientMissing,
+                    &ingredient.claim_hash,
+                ));
+                continue;
+            };
+            if parent.asset_binding != ingredient.asset_binding {
+                statuses.push(ValidationStatus::for_ingredient(
+                    StatusCode::IngredientBindingMismatch,
+                    parent_key,
+                ));
+            }
+
+            match done.get(parent_key) {
+                Some(false) => statuses.push(ValidationStatus::for_ingredient(
+                    StatusCode::IngredientCycle,
+                    parent_key,
+                )),
+                Some(true) => {}
+                None => {
+                    index.insert(parent_key, self.check_node(parent_key, &mut report));
+                    done.insert(parent_key, false);
+                    stack.push((parent_key, 0));
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Check a single node's claim and signature, returning its report index.
+    fn check_node(&self, key: &ClaimHash, report: &mut ValidationReport) -> usize {
+        let provenance = &self.manifests[key];
+        report.manifests.push(ManifestStatus {
+            manifest_id: Some(provenance.manifest_id.clone()),
+            claim_hash: key.clone(),
+            statuses: check_claim(provenance, self.trusted),
+        });
+        report.manifests.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_signer;
+    use crate::*;
+
+    fn trusted() -> TrustedKeys {
+        TrustedKeys::new().add_key(test_signer(8).verifier())
+    }
+
+    /// a, b -> c (composite) -> d
+    fn diamond() -> Vec<Provenance> {
+        let signer = test_signer(8);
+        let a = C2paBuilder::new(1u32).sign(&signer).unwrap();
+        let b = C2paBuilder::new(2u32).sign(&signer).unwrap();
+        let c = C2paBuilder::new(3u32)
+            .add_ingredient(&a, IngredientRelation::ComposedFrom)
+            .add_ingredient(&b, IngredientRelation::ComposedFrom)
+            .sign(&signer)
+            .unwrap();
+        let d = C2paBuilder::new(4u32)
+            .add_ingredient(&c, IngredientRelation::DerivedFrom)
+            .sign(&signer)
+            .unwrap();
+        [a, b, c, d].iter().map(|v| v.provenance().clone()).collect()
+    }
+
+    fn verify_all(manifests: &[Provenance], head: &ClaimHash) -> ValidationReport {
+        let trusted = trusted();
+        manifests
+            .iter()
+            .cloned()
+            .fold(ChainVerifier::new(&trusted), ChainVerifier::add_manifest)
+            .verify(head)
+    }
+
+    #[test]
+    fn test_walks_every_ingredient() {
+        let manifests = diamond();
+        let report = verify_all(&manifests, &manifests[3].claim_hash);
+
+        assert!(report.is_valid());
+        assert_eq!(report.manifests.len(), 4);
+        assert_eq!(report.manifests[0].claim_hash, manifests[3].claim_hash);
+    }
+
+    #[test]
+    fn test_reports_missing_ingredient_on_referencing_node() {
+        let manifests = diamond();
+        let without_b = [manifests[0].clone(), manifests[2].clone(), manifests[3].clone()];
+        let report = verify_all(&without_b, &manifests[3].claim_hash);
+
+        assert!(!report.is_valid());
+        let composite = report.manifest(&manifests[2].claim_hash).unwrap();
+        assert_eq!(composite.statuses.len(), 1);
+        assert_eq!(composite.statuses[0].code, StatusCode::IngredientMissing);
+        assert_eq!(composite.statuses[0].ingredient, Some(manifests[1].claim_hash.clone()));
+    }
+
+    #[test]
+    fn test_reports_invalid_signature_per_node() {
+        let mut manifests = diamond();
+        manifests[0].generator = "tampered".into();
+        let report = verify_all(&manifests, &manifests[3].claim_hash);
+
+        // Only the tampered node fails; its claim hash and signature both break
+        let failures: Vec<_> = report.failures().collect();
+        assert!(failures
+            .iter()
+            .all(|(manifest, _)| manifest.claim_hash == manifests[0].claim_hash));
+        let a = report.manifest(&manifests[0].claim_hash).unwrap();
+        assert!(a.has(StatusCode::ClaimHashMismatch));
+        assert!(a.has(StatusCode::SignatureInvalid));
+    }
+
+    #[test]
+    fn test_detects_cycles() {
+        // Forged manifests referencing each other (signatures will fail too)
+        let mut manifests = diamond();
+        let d_ref = IngredientRef {
+            claim_hash: manifests[3].claim_hash.clone(),
+            asset_binding: manifests[3].asset_binding.clone(),
+            relationship: IngredientRelation::ParentOf,
+        };
+        manifests[0].ingredients.push(d_ref);
+        let report = verify_all(&manifests, &manifests[3].claim_hash);
+
+        let a = report.manifest(&manifests[0].claim_hash).unwrap();
+        assert!(a.has(StatusCode::IngredientCycle));
+    }
+
+    #[test]
+    fn test_missing_head() {
+        let report = verify_all(&[], &ClaimHash::from_bytes([0; 32]));
+        assert!(report.has(StatusCode::ManifestMissing));
+    }
+}
@@ -0,0 +1,324 @@
+//! Canonical claim serialization.
+//!
+//! A claim is encoded as a deterministic CBOR map. Every field is tagged
+//! by name and length-delimited, so bytes cannot shift between fields
+//! without changing the encoding:
+//!
+//! ```text
+//! {
+//!   "version":     1,
+//!   "generator":   tstr,
+//!   "binding":     binding,
+//!   "ingredients": [ ingredient ],
+//!   "assertions":  [ { "label": tstr, "mime_type": tstr, "data": bstr } ],
+//! }
+//!
+//! ingredient = { "claim_hash": bstr, ? "alg": tstr, "binding": binding, "relationship": tstr }
+//!
+//! binding = { "type": "hash", "hash": bstr, ? "alg": tstr }
+//!         / { "type": "box", "offset": uint, "length": uint, "hash": bstr, ? "alg": tstr }
+//! ```
+//!
+//! `"alg"` names the algorithm of the hash beside it and is omitted for
+//! SHA-256 (see the `hash` module).
+
+use crate::cbor::Value;
+use crate::{
+    AssetBinding, ClaimHash, ContentHash, CustomAssertion, HashAlg, IngredientRef, TransformError,
+};
+
+/// Version of the claim encoding, recorded in every claim.
+pub const CLAIM_FORMAT_VERSION: u64 = 1;
+
+/// Claim contents decoded from their canonical form.
+pub(crate) struct ClaimParts {
+    pub generator: String,
+    pub binding: AssetBinding,
+    pub ingredients: Vec<IngredientRef>,
+    pub assertions: Vec<CustomAssertion>,
+}
+
+/// Serialize the claim contents into the bytes that are hashed and signed.
+pub(crate) fn encode_claim(
+    generator: &str,
+    binding: &AssetBinding,
+    ingredients: &[IngredientRef],
+    assertions: &[CustomAssertion],
+) -> Vec<u8> {
+    claim_value(generator, binding, ingredients, assertions).to_bytes()
+}
+
+/// Build the canonical CBOR structure of a claim.
+pub(crate) fn claim_value(
+    generator: &str,
+    binding: &AssetBinding,
+    ingredients: &[IngredientRef],
+    assertions: &[CustomAssertion],
+) -> Value {
+    let ingredients = ingredients.iter().map(ingredient_value).collect();
+
+    let assertions = assertions
+        .iter()
+        .map(|assertion| {
+            Value::map([
+                ("label", Value::text(&assertion.label)),
+                ("mime_type", Value::text(&assertion.mime_type)),
+                ("data", Value::bytes(assertion.data.clone())),
+            ])
+        })
+        .collect();
+
+    Value::map([
+        ("version", Value::uint(CLAIM_FORMAT_VERSION)),
+        ("generator", Value::text(generator)),
+        ("binding", binding_value(binding)),
+        ("ingredients", Value::Array(ingredients)),
+        ("assertions", Value::Array(assertions)),
+    ])
+}
+
+/// Inverse of [`claim_value`].
+pub(crate) fn decode_claim(value: &Value) -> Result<ClaimParts, TransformError> {
+    let version = field(value, "version")?
+        .as_uint()
+        .ok_or_else(|| malformed("version"))?;
+    if version != CLAIM_FORMAT_VERSION {
+        return Err(TransformError::Manifest(format!(
+            "unsupported claim format version {}",
+            version
+        )));
+    }
+
+    let ingredients = array(value, "ingredients")?
+        .iter()
+        .map(|ingredient| {
+            Ok(IngredientRef {
+                claim_hash: claim_hash(ingredient, "claim_hash")?,
+                asset_binding: decode_binding(field(ingredient, "binding")?)?,
+                relationship: text(ingredient, "relationship")?
+                    .parse()
+                    .map_err(|_| malformed("relationship"))?,
+            })
+        })
+        .collect::<Result<_, TransformError>>()?;
+
+    let assertions = array(value, "assertions")?
+        .iter()
+        .map(|assertion| {
+            Ok(CustomAssertion {
+                label: text(assertion, "label")?.to_string(),
+                mime_type: text(assertion, "mime_type")?.to_string(),
+                data: bytes(assertion, "data")?.to_vec(),
+            })
+        })
+        .collect::<Result<_, TransformError>>()?;
+
+    Ok(ClaimParts {
+        generator: text(value, "generator")?.to_string(),
+        binding: decode_binding(field(value, "binding")?)?,
+        ingredients,
+        assertions,
+    })
+}
+
+pub(crate) fn ingredient_value(ingredient: &IngredientRef) -> Value {
+    let entry = Value::map([
+        ("claim_hash", Value::bytes(ingredient.claim_hash.as_bytes())),
+        ("binding", binding_value(&ingredient.asset_binding)),
+        ("relationship", Value::text(ingredient.relationship.as_str())),
+    ]);
+    with_hash_alg(entry, ingredient.claim_hash.alg())
+}
+
+pub(crate) fn binding_value(binding: &AssetBinding) -> Value {
+    let (value, hash) = match binding {
+        AssetBinding::Hash(hash) => (
+            Value::map([
+                ("type", Value::text("hash")),
+                ("hash", Value::bytes(hash.as_bytes())),
+            ]),
+            hash,
+        ),
+        AssetBinding::Box { offset, length, hash } => (
+            Value::map([
+                ("type", Value::text("box")),
+                ("offset", Value::uint(*offset)),
+                ("length", Value::uint(*length)),
+                ("hash", Value::bytes(hash.as_bytes())),
+            ]),
+            hash,
+        ),
+    };
+    with_hash_alg(value, hash.alg())
+}
+
+fn decode_binding(value: &Value) -> Result<AssetBinding, TransformError> {
+    let hash = ContentHash::new(hash_alg(value)?, bytes(value, "hash")?.to_vec())
+        .map_err(|_| malformed("hash"))?;
+    match text(value, "type")? {
+        "hash" => Ok(AssetBinding::Hash(hash)),
+        "box" => Ok(AssetBinding::Box {
+            offset: uint(value, "offset")?,
+            length: uint(value, "length")?,
+            hash,
+        }),
+        _ => Err(malformed("binding type")),
+    }
+}
+
+// Field accessors that report which field was missing or mistyped.
+
+pub(crate) fn malformed(name: &str) -> TransformError {
+    TransformError::Manifest(format!("missing or malformed field `{}`", name))
+}
+
+pub(crate) fn field<'a>(value: &'a Value, name: &str) -> Result<&'a Value, TransformError> {
+    value.get(name).ok_or_else(|| malformed(name))
+}
+
+pub(crate) fn text<'a>(value: &'a Value, name: &str) -> Result<&'a str, TransformError> {
+    field(value, name)?.as_text().ok_or_else(|| malformed(name))
+}
+
+pub(crate) fn bytes<'a>(value: &'a Value, name: &str) -> Result<&'a [u8], TransformError> {
+    field(value, name)?.as_bytes().ok_or_else(|| malformed(name))
+}
+
+pub(crate) fn uint(value: &Value, name: &str) -> Result<u64, TransformError> {
+    field(value, name)?.as_uint().ok_or_else(|| malformed(name))
+}
+
+pub(crate) fn array<'a>(value: &'a Value, name: &str) -> Result<&'a [Value], TransformError> {
+    field(value, name)?.as_array().ok_or_else(|| malformed(name))
+}
+
+pub(crate) fn hash32(value: &Value, name: &str) -> Result<[u8; 32], TransformError> {
+    bytes(value, name)?.try_into().map_err(|_| malformed(name))
+}
+
+/// Record the algorithm of a hash stored in the map `value`.
+pub(crate) fn with_hash_alg(mut value: Value, alg: HashAlg) -> Value {
+    if let (Value::Map(entries), false) = (&mut value, alg == HashAlg::Sha256) {
+        entries.push((Value::text("alg"), Value::text(alg.as_str())));
+    }
+    value
+}
+
+/// Algorithm of the hash stored in the map `value`; SHA-256 if unrecorded.
+pub(crate) fn hash_alg(value: &Value) -> Result<HashAlg, TransformError> {
+    match value.get("alg") {
+        Some(alg) => alg.as_text().ok_or_else(|| malformed("alg"))?.parse(),
+        None => Ok(HashAlg::Sha256),
+    }
+}
+
+/// Claim hash stored under `name`, with the algorithm recorded beside it.
+pub(crate) fn claim_hash(value: &Value, name: &str) -> Result<ClaimHash, TransformError> {
+    ClaimHash::new(hash_alg(value)?, bytes(value, name)?.to_vec()).map_err(|_| malformed(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IngredientRelation;
+
+    fn hash_binding() -> AssetBinding {
+        AssetBinding::Hash(ContentHash::from_bytes([1; 32]))
+    }
+
+    fn ingredient(relationship: IngredientRelation) -> IngredientRef {
+        IngredientRef {
+            claim_hash: ClaimHash::from_bytes([2; 32]),
+            asset_binding: hash_binding(),
+            relationship,
+        }
+    }
+
+    fn assertion(label: &str, data: &[u8], mime_type: &str) -> CustomAssertion {
+        CustomAssertion {
+            label: label.into(),
+            data: data.to_vec(),
+            mime_type: mime_type.into(),
+        }
+    }
+
+    #[test]
+    fn test_fields_cannot_shift_bytes() {
+        let a = encode_claim("gen", &hash_binding(), &[], &[assertion("ab", b"c", "text/plain")]);
+        let b = encode_claim("gen", &hash_binding(), &[], &[assertion("a", b"bc", "text/plain")]);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_box_binding_is_encoded() {
+        let at = |offset| AssetBinding::Box {
+            offset,
+            length: 10,
+            hash: ContentHash::from_bytes([1; 32]),
+        };
+        assert_ne!(
+            encode_claim("gen", &at(0), &[], &[]),
+            encode_claim("gen", &at(8), &[], &[])
+        );
+        assert_ne!(
+            encode_claim("gen", &at(0), &[], &[]),
+            encode_claim("gen", &hash_binding(), &[], &[])
+        );
+    }
+
+    #[test]
+    fn test_relationship_and_mime_type_are_encoded() {
+        assert_ne!(
+            encode_claim("gen", &hash_binding(), &[ingredient(IngredientRelation::ParentOf)], &[]),
+            encode_claim("gen", &hash_binding(), &[ingredient(IngredientRelation::ComponentOf)], &[])
+        );
+        assert_ne!(
+            encode_claim("gen", &hash_binding(), &[], &[assertion("l", b"{}", "application/json")]),
+            encode_claim("gen", &hash_binding(), &[], &[assertion("l", b"{}", "application/cbor")])
+        );
+    }
+
+    #[test]
+    fn test_decode_roundtrip() {
+        let box_binding = AssetBinding::Box {
+            offset: 8,
+            length: 100,
+            hash: ContentHash::from_bytes([3; 32]),
+        };
+        let ingredients = [ingredient(IngredientRelation::ComposedFrom)];
+        let assertions = [assertion("c2pa.test", b"{}", "application/json")];
+        let value = claim_value("gen", &box_binding, &ingredients, &assertions);
+
+        let parts = decode_claim(&value).unwrap();
+        let reencoded = encode_claim(
+            &parts.generator,
+            &parts.binding,
+            &parts.ingredients,
+            &parts.assertions,
+        );
+        assert_eq!(reencoded, value.to_bytes());
+    }
+
+    #[test]
+    fn test_hash_alg_recorded_unless_sha256() {
+        let binding = |alg| AssetBinding::Hash(ContentHash::compute_with(alg, b"data"));
+        let sha256 = binding_value(&binding(HashAlg::Sha256));
+        assert!(sha256.get("alg").is_none());
+        assert_eq!(decode_binding(&sha256).unwrap(), binding(HashAlg::Sha256));
+
+        let blake3 = binding_value(&binding(HashAlg::Blake3));
+        assert_eq!(blake3.get("alg").and_then(Value::as_text), Some("blake3"));
+        assert_eq!(decode_binding(&blake3).unwrap(), binding(HashAlg::Blake3));
+
+        // A digest whose length does not fit the recorded algorithm
+        let wrong = with_hash_alg(sha256, HashAlg::Sha512);
+        assert!(decode_binding(&wrong).is_err());
+    }
+
+    #[test]
+    fn test_encoding_records_version() {
+        let bytes = encode_claim("gen", &hash_binding(), &[], &[]);
+        let version_field = [b"\x67version".as_slice(), &[CLAIM_FORMAT_VERSION as u8]].concat();
+        assert!(bytes.windows(version_field.len()).any(|w| w == version_field));
+    }
+}
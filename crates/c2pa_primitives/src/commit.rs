@@ -0,0 +1,350 @@
+//! Salted commitments to recorded transform parameters.
+//!
+//! A commitment is `SHA-256(domain || name || salt || value)` with a fresh
+//! random 32-byte salt, so low-entropy values cannot be recovered by hashing
+//! candidates. Only the commitment goes into the manifest; the producer keeps
+//! the [`ParamOpening`] and can later reveal it to an auditor, who checks it
+//! against the manifest with [`ParamOpening::opens`].
+//!
+//! Values are encoded through [`C2paCommit`], a canonical encoding that
+//! does not depend on `Debug` output, field order or the toolchain:
+//!
+//! ```text
+//! integers, bool   -> CBOR int (bool as 0 / 1)
+//! floats           -> CBOR bstr of the IEEE 754 bits, big-endian
+//! str, String      -> CBOR tstr
+//! [T], Vec<T>      -> CBOR array of bstr (each element's encoding)
+//! Option<T>        -> CBOR array of zero or one bstr
+//! derived struct   -> CBOR map { field name / index: bstr }
+//! derived enum     -> CBOR map { "variant": tstr, "fields": struct map }
+//! ```
+
+use sha2::{Digest, Sha256};
+
+use crate::cbor::Value;
+use crate::{Provenance, TransformError};
+
+/// Domain separator for parameter commitments.
+const PARAM_COMMIT_DOMAIN: &[u8] = b"c2pa_primitives/param-commit/v1";
+
+/// Everything needed to open a parameter commitment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamOpening {
+    /// Parameter name, as recorded in the `c2pa.transform` assertion.
+    pub name: String,
+    /// Random blinding salt. Keep this private until the value is revealed.
+    pub salt: [u8; 32],
+    /// Encoded parameter value.
+    pub value: Vec<u8>,
+}
+
+impl ParamOpening {
+    /// Commit to `value` under `name` with a fresh random salt.
+    pub fn new(name: impl Into<String>, value: Vec<u8>) -> Result<Self, TransformError> {
+        let mut salt = [0u8; 32];
+        getrandom::getrandom(&mut salt)
+            .map_err(|e| TransformError::C2pa(format!("no randomness for salt: {}", e)))?;
+        Ok(Self {
+            name: name.into(),
+            salt,
+            value,
+        })
+    }
+
+    /// The commitment stored in the manifest.
+    pub fn commitment(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(PARAM_COMMIT_DOMAIN);
+        // Length prefixes keep name, salt and value from running together
+        hasher.update((self.name.len() as u64).to_be_bytes());
+        hasher.update(self.name.as_bytes());
+        hasher.update(self.salt);
+        hasher.update((self.value.len() as u64).to_be_bytes());
+        hasher.update(&self.value);
+        hasher.finalize().into()
+    }
+
+    /// True if this opening matches `commitment`.
+    pub fn verify(&self, commitment: &[u8; 32]) -> bool {
+        &self.commitment() == commitment
+    }
+
+    /// True if this opening matches the commitment recorded for its
+    /// parameter in the manifest's `c2pa.transform` assertion.
+    pub fn opens(&self, provenance: &Provenance) -> bool {
+        recorded_commitments(provenance)
+            .iter()
+            .any(|(name, commitment)| name == &self.name && self.verify(commitment))
+    }
+}
+
+// ============================================================================
+// Trait: C2paCommit - Canonical parameter encoding
+// ============================================================================
+
+/// Types that can be recorded with `record(params(...))`.
+///
+/// [`commit_bytes`](Self::commit_bytes) must be a pure function of the value:
+/// equal values give equal bytes on every build. Derive it to encode every
+/// field by name:
+///
+/// ```
+/// use c2pa_primitives::C2paCommit;
+///
+/// #[derive(C2paCommit)]
+/// struct Threshold {
+///     level: u8,
+///     #[c2pa_commit(skip)]
+///     note: String,
+/// }
+///
+/// #[derive(C2paCommit)]
+/// enum Mode {
+///     Fast,
+///     Exact { tolerance: f64 },
+/// }
+///
+/// let a = Threshold { level: 3, note: "a".into() };
+/// let b = Threshold { level: 3, note: "b".into() };
+/// assert_eq!(a.commit_bytes(), b.commit_bytes());
+/// assert_ne!(Mode::Fast.commit_bytes(), Mode::Exact { tolerance: 0.1 }.commit_bytes());
+/// ```
+pub trait C2paCommit {
+    /// Canonical encoding of the value.
+    fn commit_bytes(&self) -> Vec<u8>;
+}
+
+/// Encode named fields as a deterministic CBOR map of name to encoding.
+///
+/// Independent of field order. Used by `#[derive(C2paCommit)]` on structs.
+pub fn commit_fields(fields: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    fields_value(fields).to_bytes()
+}
+
+/// Encode an enum variant and its fields.
+///
+/// Used by `#[derive(C2paCommit)]` on enums.
+pub fn commit_variant(variant: &str, fields: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    Value::map([
+        ("variant", Value::text(variant)),
+        ("fields", fields_value(fields)),
+    ])
+    .to_bytes()
+}
+
+fn fields_value(fields: &[(&str, Vec<u8>)]) -> Value {
+    Value::Map(
+        fields
+            .iter()
+            .map(|(name, bytes)| (Value::text(*name), Value::bytes(bytes.clone())))
+            .collect(),
+    )
+}
+
+fn elements<'a, T: C2paCommit + 'a>(items: impl IntoIterator<Item = &'a T>) -> Vec<u8> {
+    let items = items
+        .into_iter()
+        .map(|item| Value::bytes(item.commit_bytes()))
+        .collect();
+    Value::Array(items).to_bytes()
+}
+
+macro_rules! impl_commit_for_int {
+    ($($ty:ty),*) => {
+        $(
+            impl C2paCommit for $ty {
+                fn commit_bytes(&self) -> Vec<u8> {
+                    Value::Int(*self as i128).to_bytes()
+                }
+            }
+        )*
+    };
+}
+
+impl_commit_for_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+// 128-bit values outside the CBOR integer range fall back to their bytes
+impl C2paCommit for u128 {
+    fn commit_bytes(&self) -> Vec<u8> {
+        match u64::try_from(*self) {
+            Ok(n) => Value::uint(n).to_bytes(),
+            Err(_) => Value::bytes(self.to_be_bytes()).to_bytes(),
+        }
+    }
+}
+
+impl C2paCommit for i128 {
+    fn commit_bytes(&self) -> Vec<u8> {
+        match i64::try_from(*self) {
+            Ok(n) => Value::Int(n as i128).to_bytes(),
+            Err(_) => Value::bytes(self.to_be_bytes()).to_bytes(),
+        }
+    }
+}
+
+impl C2paCommit for bool {
+    fn commit_bytes(&self) -> Vec<u8> {
+        Value::Int(*self as i128).to_bytes()
+    }
+}
+
+impl C2paCommit for f32 {
+    fn commit_bytes(&self) -> Vec<u8> {
+        Value::bytes(self.to_bits().to_be_bytes()).to_bytes()
+    }
+}
+
+impl C2paCommit for f64 {
+    fn commit_bytes(&self) -> Vec<u8> {
+        Value::bytes(self.to_bits().to_be_bytes()).to_bytes()
+    }
+}
+
+impl C2paCommit for char {
+    fn commit_bytes(&self) -> Vec<u8> {
+        Value::text(self.to_string()).to_bytes()
+    }
+}
+
+impl C2paCommit for str {
+    fn commit_bytes(&self) -> Vec<u8> {
+        Value::text(self).to_bytes()
+    }
+}
+
+impl C2paCommit for String {
+    fn commit_bytes(&self) -> Vec<u8> {
+        self.as_str().commit_bytes()
+    }
+}
+
+impl<T: C2paCommit> C2paCommit for [T] {
+    fn commit_bytes(&self) -> Vec<u8> {
+        elements(self)
+    }
+}
+
+impl<T: C2paCommit, const N: usize> C2paCommit for [T; N] {
+    fn commit_bytes(&self) -> Vec<u8> {
+        elements(self)
+    }
+}
+
+impl<T: C2paCommit> C2paCommit for Vec<T> {
+    fn commit_bytes(&self) -> Vec<u8> {
+        elements(self)
+    }
+}
+
+impl<T: C2paCommit> C2paCommit for Option<T> {
+    fn commit_bytes(&self) -> Vec<u8> {
+        elements(self)
+    }
+}
+
+impl<T: C2paCommit + ?Sized> C2paCommit for &T {
+    fn commit_bytes(&self) -> Vec<u8> {
+        (**self).commit_bytes()
+    }
+}
+
+impl<T: C2paCommit + ?Sized> C2paCommit for Box<T> {
+    fn commit_bytes(&self) -> Vec<u8> {
+        (**self).commit_bytes()
+    }
+}
+
+/// Parameter commitments recorded in a manifest's `c2pa.transform` assertion.
+pub fn recorded_commitments(provenance: &Provenance) -> Vec<(String, [u8; 32])> {
+    provenance
+        .assertions
+        .iter()
+        .filter(|assertion| assertion.label == "c2pa.transform")
+        .flat_map(|assertion| parse_param_commits(&assertion.data))
+        .collect()
+}
+
+/// Read `{"param_commits": {"name": "hex", ...}}` from the assertion JSON.
+///
+/// Malformed entries are skipped rather than failing the whole manifest.
+fn parse_param_commits(json: &[u8]) -> Vec<(String, [u8; 32])> {
+    let Ok(serde_json::Value::Object(mut assertion)) = serde_json::from_slice(json) else {
+        return Vec::new();
+    };
+    let Some(serde_json::Value::Object(commits)) = assertion.remove("param_commits") else {
+        return Vec::new();
+    };
+
+    commits
+        .into_iter()
+        .filter_map(|(name, hash)| {
+            let hash = hex::decode(hash.as_str()?).ok()?;
+            Some((name, hash.try_into().ok()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_salt_blinds_equal_values() {
+        let a = ParamOpening::new("threshold", b"3".to_vec()).unwrap();
+        let b = ParamOpening::new("threshold", b"3".to_vec()).unwrap();
+        assert_ne!(a.salt, b.salt);
+        assert_ne!(a.commitment(), b.commitment());
+    }
+
+    #[test]
+    fn test_opening_must_match_value_and_salt() {
+        let opening = ParamOpening::new("threshold", b"3".to_vec()).unwrap();
+        let commitment = opening.commitment();
+        assert!(opening.verify(&commitment));
+
+        let wrong_value = ParamOpening {
+            value: b"4".to_vec(),
+            ..opening.clone()
+        };
+        assert!(!wrong_value.verify(&commitment));
+
+        let wrong_salt = ParamOpening {
+            salt: [0; 32],
+            ..opening
+        };
+        assert!(!wrong_salt.verify(&commitment));
+    }
+
+    #[test]
+    fn test_commit_encoding_is_canonical() {
+        // Width does not matter, only the value
+        assert_eq!(3u8.commit_bytes(), 3u64.commit_bytes());
+        assert_eq!((-1i32).commit_bytes(), [0x20]);
+        assert_eq!("ab".commit_bytes(), b"\x62ab");
+        assert_eq!(1.5f64.commit_bytes(), [&[0x48][..], &1.5f64.to_bits().to_be_bytes()].concat());
+        assert_eq!(u128::MAX.commit_bytes()[0], 0x50);
+
+        assert_ne!(Some(0u8).commit_bytes(), None::<u8>.commit_bytes());
+        assert_ne!(vec![1u8, 2].commit_bytes(), vec![2u8, 1].commit_bytes());
+        assert_eq!(
+            commit_fields(&[("a", 1u8.commit_bytes()), ("b", 2u8.commit_bytes())]),
+            commit_fields(&[("b", 2u8.commit_bytes()), ("a", 1u8.commit_bytes())])
+        );
+    }
+
+    #[test]
+    fn test_parse_param_commits() {
+        let hash = "ab".repeat(32);
+        let json = format!(
+            r#"{{"transform":"t","param_commits":{{"a":"{}","b\"}}":"{}"}},"pipeline":["p"]}}"#,
+            hash, hash
+        );
+        let commits = parse_param_commits(json.as_bytes());
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[1], ("b\"}".to_string(), [0xab; 32]));
+
+        assert!(parse_param_commits(br#"{"transform":"t","param_commits":{}}"#).is_empty());
+        assert!(parse_param_commits(br#"{"param_commits":{"a":"abcd"}}"#).is_empty());
+        assert!(parse_param_commits(b"not json").is_empty());
+    }
+}
@@ -0,0 +1,386 @@
+//! COSE_Sign1 envelopes (RFC 9052) for claim signatures.
+//!
+//! A claim signed with [`C2paBuilder::cose_sign1`](crate::C2paBuilder::cose_sign1)
+//! is signed through a COSE_Sign1 structure instead of over the bare claim
+//! bytes, so standard COSE tooling can check the signature:
+//!
+//! ```text
+//! COSE_Sign1 = #6.18([
+//!   protected:   bstr .cbor { 1: alg, 4: kid, ? 33: x5chain },
+//!   unprotected: { ? "timestamp": timestamp },   ; see `timestamp` module
+//!   payload:     bstr / nil,                     ; the claim; nil if detached
+//!   signature:   bstr,
+//! ])
+//!
+//! Sig_structure = ["Signature1", protected, h'', payload]
+//! ```
+//!
+//! `x5chain` is a single bstr for a one-certificate chain and an array
+//! otherwise (RFC 9360). The protected header bytes are kept in
+//! [`ClaimSignature::protected_header`], so the envelope can be rebuilt
+//! exactly from stored provenance.
+
+use crate::cbor::Value;
+use crate::timestamp;
+use crate::{
+    ClaimSignature, Provenance, SignatureVerifier, Signer, SigningAlg, TimestampToken,
+    TransformError,
+};
+
+/// CBOR tag of a COSE_Sign1 message.
+pub const COSE_SIGN1_TAG: u64 = 18;
+
+// Header labels (RFC 9052 §3.1, RFC 9360 §2).
+const ALG: i128 = 1;
+const KID: i128 = 4;
+const X5CHAIN: i128 = 33;
+
+/// Unprotected header label of the timestamp token.
+const TIMESTAMP: &str = "timestamp";
+
+/// COSE algorithm identifier of a signing algorithm.
+fn cose_alg(algorithm: SigningAlg) -> Result<i128, TransformError> {
+    match algorithm {
+        SigningAlg::Ed25519 => Ok(-8),
+        SigningAlg::Es256 => Ok(-7),
+        SigningAlg::Ps256 => Ok(-37),
+        SigningAlg::Placeholder => Err(TransformError::Signing(
+            "placeholder signatures have no COSE algorithm".into(),
+        )),
+    }
+}
+
+fn signing_alg(id: i128) -> Result<SigningAlg, TransformError> {
+    match id {
+        -8 => Ok(SigningAlg::Ed25519),
+        -7 => Ok(SigningAlg::Es256),
+        -37 => Ok(SigningAlg::Ps256),
+        other => Err(malformed(&format!("unsupported COSE algorithm {}", other))),
+    }
+}
+
+fn malformed(what: &str) -> TransformError {
+    TransformError::Manifest(format!("invalid COSE_Sign1: {}", what))
+}
+
+/// A COSE_Sign1 message carrying a claim signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoseSign1 {
+    /// Serialized protected header, exactly as signed.
+    pub protected: Vec<u8>,
+    pub algorithm: SigningAlg,
+    pub key_id: Vec<u8>,
+    /// Certificate chain from `x5chain`, leaf first. May be empty.
+    pub certificate_chain: Vec<Vec<u8>>,
+    /// Timestamp token from the unprotected header.
+    pub timestamp: Option<TimestampToken>,
+    /// Attached payload; `None` if detached.
+    pub payload: Option<Vec<u8>>,
+    pub signature: Vec<u8>,
+}
+
+impl CoseSign1 {
+    /// Sign `payload` with `signer`, attaching it to the message.
+    pub fn sign(payload: &[u8], signer: &dyn Signer) -> Result<Self, TransformError> {
+        let key_id = signer.key_id();
+        let protected = protected_header(signer.algorithm(), &key_id, signer.certificate_chain())?;
+        let signature = signer.sign(&sig_structure(&protected, payload))?;
+        Ok(Self {
+            protected,
+            algorithm: signer.algorithm(),
+            key_id,
+            certificate_chain: signer.certificate_chain().to_vec(),
+            timestamp: None,
+            payload: Some(payload.to_vec()),
+            signature,
+        })
+    }
+
+    /// The envelope of a claim signed through COSE_Sign1, with the claim
+    /// attached as payload.
+    pub fn from_provenance(provenance: &Provenance) -> Result<Self, TransformError> {
+        let signature = provenance
+            .signature
+            .as_ref()
+            .ok_or_else(|| TransformError::C2pa("manifest has no claim signature".into()))?;
+        let protected = signature.protected_header.clone().ok_or_else(|| {
+            TransformError::C2pa("claim was not signed through a COSE_Sign1 envelope".into())
+        })?;
+        Ok(Self {
+            protected,
+            algorithm: signature.algorithm,
+            key_id: signature.key_id.clone(),
+            certificate_chain: signature.certificate_chain.clone(),
+            timestamp: signature.timestamp.clone(),
+            payload: Some(provenance.claim_bytes()),
+            signature: signature.signature.clone(),
+        })
+    }
+
+    /// Drop the payload; verifiers must then supply it.
+    pub fn detached(mut self) -> Self {
+        self.payload = None;
+        self
+    }
+
+    /// The claim signature this envelope carries.
+    pub fn to_claim_signature(&self) -> ClaimSignature {
+        ClaimSignature {
+            algorithm: self.algorithm,
+            signature: self.signature.clone(),
+            key_id: self.key_id.clone(),
+            certificate_chain: self.certificate_chain.clone(),
+            timestamp: self.timestamp.clone(),
+            protected_header: Some(self.protected.clone()),
+        }
+    }
+
+    /// Check the signature with `verifier`.
+    ///
+    /// `detached_payload` is required if, and only if, the message has no
+    /// attached payload.
+    pub fn verify(
+        &self,
+        verifier: &dyn SignatureVerifier,
+        detached_payload: Option<&[u8]>,
+    ) -> Result<(), TransformError> {
+        let payload = match (&self.payload, detached_payload) {
+            (Some(payload), None) => payload.as_slice(),
+            (None, Some(payload)) => payload,
+            (Some(_), Some(_)) => {
+                return Err(TransformError::Verification("payload is already attached".into()))
+            }
+            (None, None) => {
+                return Err(TransformError::Verification("detached payload is missing".into()))
+            }
+        };
+        if verifier.algorithm() != self.algorithm || verifier.key_id() != self.key_id {
+            return Err(TransformError::Verification(
+                "verifier does not hold the signing key".into(),
+            ));
+        }
+        verifier.verify(&sig_structure(&self.protected, payload), &self.signature)
+    }
+
+    /// Encode as a tagged COSE_Sign1 message.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut unprotected = Vec::new();
+        if let Some(token) = &self.timestamp {
+            unprotected.push((Value::text(TIMESTAMP), timestamp::token_value(token)));
+        }
+        let payload = match &self.payload {
+            Some(payload) => Value::bytes(payload.clone()),
+            None => Value::Null,
+        };
+        let message = Value::Array(vec![
+            Value::bytes(self.protected.clone()),
+            Value::Map(unprotected),
+            payload,
+            Value::bytes(self.signature.clone()),
+        ]);
+        Value::Tag(COSE_SIGN1_TAG, Box::new(message)).to_bytes()
+    }
+
+    /// Parse a COSE_Sign1 message, tagged or not.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, TransformError> {
+        let value = Value::from_bytes(data).map_err(|e| malformed(e.0))?;
+        let message = match value {
+            Value::Tag(COSE_SIGN1_TAG, message) => *message,
+            Value::Tag(..) => return Err(malformed("not a COSE_Sign1 tag")),
+            message => message,
+        };
+        let [protected, unprotected, payload, signature] = match message {
+            Value::Array(items) => <[Value; 4]>::try_from(items)
+                .map_err(|_| malformed("expected four elements"))?,
+            _ => return Err(malformed("expected an array")),
+        };
+
+        let protected = protected
+            .as_bytes()
+            .ok_or_else(|| malformed("protected header"))?
+            .to_vec();
+        let signed = Headers::decode(&protected)?;
+        if !matches!(unprotected, Value::Map(_)) {
+            return Err(malformed("unprotected header"));
+        }
+        let unsigned = Headers::parse(&unprotected)?;
+        let timestamp = unprotected
+            .get(TIMESTAMP)
+            .map(timestamp::decode_token)
+            .transpose()?;
+        let payload = match payload {
+            Value::Bytes(payload) => Some(payload),
+            Value::Null => None,
+            _ => return Err(malformed("payload")),
+        };
+
+        Ok(Self {
+            protected,
+            // The algorithm must be protected; the rest may be either
+            algorithm: signed.alg.ok_or_else(|| malformed("missing protected alg"))?,
+            key_id: signed.kid.or(unsigned.kid).unwrap_or_default(),
+            certificate_chain: signed.x5chain.or(unsigned.x5chain).unwrap_or_default(),
+            timestamp,
+            payload,
+            signature: signature
+                .as_bytes()
+                .ok_or_else(|| malformed("signature"))?
+                .to_vec(),
+        })
+    }
+}
+
+/// Headers understood by this module.
+#[derive(Default)]
+pub(crate) struct Headers {
+    pub alg: Option<SigningAlg>,
+    pub kid: Option<Vec<u8>>,
+    pub x5chain: Option<Vec<Vec<u8>>>,
+}
+
+impl Headers {
+    /// Parse a serialized protected header; empty means no headers.
+    pub(crate) fn decode(protected: &[u8]) -> Result<Self, TransformError> {
+        if protected.is_empty() {
+            return Ok(Self::default());
+        }
+        Self::parse(&Value::from_bytes(protected).map_err(|e| malformed(e.0))?)
+    }
+
+    fn parse(map: &Value) -> Result<Self, TransformError> {
+        if !matches!(map, Value::Map(_)) {
+            return Err(malformed("header is not a map"));
+        }
+        let alg = match map.get_int(ALG) {
+            Some(Value::Int(id)) => Some(signing_alg(*id)?),
+            Some(_) => return Err(malformed("alg")),
+            None => None,
+        };
+        let kid = match map.get_int(KID) {
+            Some(kid) => Some(kid.as_bytes().ok_or_else(|| malformed("kid"))?.to_vec()),
+            None => None,
+        };
+        let x5chain = match map.get_int(X5CHAIN) {
+            Some(Value::Bytes(cert)) => Some(vec![cert.clone()]),
+            Some(Value::Array(certs)) => Some(
+                certs
+                    .iter()
+                    .map(|cert| cert.as_bytes().map(<[u8]>::to_vec))
+                    .collect::<Option<_>>()
+                    .ok_or_else(|| malformed("x5chain"))?,
+            ),
+            Some(_) => return Err(malformed("x5chain")),
+            None => None,
+        };
+        Ok(Self { alg, kid, x5chain })
+    }
+}
+
+/// Serialized protected header for a signature by `algorithm` and `key_id`.
+pub(crate) fn protected_header(
+    algorithm: SigningAlg,
+    key_id: &[u8],
+    certificate_chain: &[Vec<u8>],
+) -> Result<Vec<u8>, TransformError> {
+    let mut entries = vec![
+        (Value::Int(ALG), Value::Int(cose_alg(algorithm)?)),
+        (Value::Int(KID), Value::bytes(key_id)),
+    ];
+    match certificate_chain {
+        [] => {}
+        [leaf] => entries.push((Value::Int(X5CHAIN), Value::bytes(leaf.clone()))),
+        chain => {
+            let certs = chain.iter().map(|cert| Value::bytes(cert.clone())).collect();
+            entries.push((Value::Int(X5CHAIN), Value::Array(certs)));
+        }
+    }
+    Ok(Value::Map(entries).to_bytes())
+}
+
+/// The bytes a COSE_Sign1 signature covers, with no external AAD.
+pub(crate) fn sig_structure(protected: &[u8], payload: &[u8]) -> Vec<u8> {
+    Value::Array(vec![
+        Value::text("Signature1"),
+        Value::bytes(protected),
+        Value::bytes(Vec::new()),
+        Value::bytes(payload),
+    ])
+    .to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_signer;
+    use crate::{C2paBuilder, P256Signer};
+
+    #[test]
+    fn test_sig_structure_layout() {
+        // ["Signature1", h'a10127', h'', h'']
+        let expected = [
+            b"\x84\x6aSignature1".as_slice(),
+            &[0x43, 0xa1, 0x01, 0x27],
+            &[0x40, 0x40],
+        ]
+        .concat();
+        assert_eq!(sig_structure(&[0xa1, 0x01, 0x27], b""), expected);
+    }
+
+    #[test]
+    fn test_sign_encode_decode_verify() {
+        let signer = test_signer(8);
+        let message = CoseSign1::sign(b"claim", &signer).unwrap();
+        let bytes = message.to_bytes();
+        assert_eq!(bytes[0], 0xd2); // tag 18
+
+        let decoded = CoseSign1::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, message);
+        assert_eq!(decoded.algorithm, SigningAlg::Ed25519);
+        assert!(decoded.verify(&signer.verifier(), None).is_ok());
+
+        let other = test_signer(9);
+        assert!(decoded.verify(&other.verifier(), None).is_err());
+
+        let detached = CoseSign1::from_bytes(&message.clone().detached().to_bytes()).unwrap();
+        assert!(detached.verify(&signer.verifier(), None).is_err());
+        assert!(detached.verify(&signer.verifier(), Some(b"forged")).is_err());
+        assert!(detached.verify(&signer.verifier(), Some(b"claim")).is_ok());
+    }
+
+    #[test]
+    fn test_headers_carry_algorithm_and_chain() {
+        let p256 = P256Signer::from_raw(&[3u8; 32])
+            .unwrap()
+            .with_certificate_chain(vec![b"leaf".to_vec(), b"root".to_vec()]);
+        let message = CoseSign1::from_bytes(&CoseSign1::sign(b"claim", &p256).unwrap().to_bytes())
+            .unwrap();
+        let headers = Headers::decode(&message.protected).unwrap();
+        assert_eq!(headers.alg, Some(SigningAlg::Es256));
+        assert_eq!(headers.kid, Some(p256.key_id()));
+        assert_eq!(message.certificate_chain, [b"leaf".to_vec(), b"root".to_vec()]);
+        assert!(message.verify(&p256.verifier(), None).is_ok());
+
+        assert!(CoseSign1::sign(b"claim", &crate::TestSigner).is_err());
+    }
+
+    #[test]
+    fn test_envelope_from_signed_claim() {
+        let signer = test_signer(8);
+        let value = C2paBuilder::new(3u32).cose_sign1(true).sign(&signer).unwrap();
+        let message = CoseSign1::from_provenance(value.provenance()).unwrap();
+        assert_eq!(message.payload.as_deref(), Some(value.provenance().claim_bytes().as_slice()));
+        assert!(message.verify(&signer.verifier(), None).is_ok());
+
+        let plain = C2paBuilder::new(3u32).sign(&signer).unwrap();
+        assert!(CoseSign1::from_provenance(plain.provenance()).is_err());
+    }
+
+    #[test]
+    fn test_rejects_malformed_messages() {
+        assert!(CoseSign1::from_bytes(b"").is_err());
+        let untagged_short = Value::Array(vec![Value::bytes(Vec::new())]).to_bytes();
+        assert!(CoseSign1::from_bytes(&untagged_short).is_err());
+        let wrong_tag = Value::Tag(98, Box::new(Value::Array(Vec::new()))).to_bytes();
+        assert!(CoseSign1::from_bytes(&wrong_tag).is_err());
+    }
+}
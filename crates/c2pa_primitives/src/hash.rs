@@ -0,0 +1,143 @@
+//! Hash algorithms for claim and content hashes.
+//!
+//! Every [`ClaimHash`](crate::ClaimHash) and [`ContentHash`](crate::ContentHash)
+//! records the algorithm that produced it, and verification recomputes with
+//! that same algorithm. Which algorithm new manifests use is a pipeline
+//! setting ([`TransformContext::with_hash_alg`](crate::TransformContext::with_hash_alg)).
+//!
+//! Encodings store the algorithm as an `"alg"` entry next to the hash and
+//! leave it out for SHA-256, so manifests written before the algorithm was
+//! recorded decode to SHA-256 and still verify.
+
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::TransformError;
+
+/// Hash algorithms for claim and content hashes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HashAlg {
+    #[default]
+    Sha256,
+    Sha384,
+    Sha512,
+    Blake3,
+}
+
+impl HashAlg {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+            Self::Sha512 => "sha512",
+            Self::Blake3 => "blake3",
+        }
+    }
+
+    /// Length of a digest in bytes.
+    pub fn output_len(&self) -> usize {
+        match self {
+            Self::Sha256 | Self::Blake3 => 32,
+            Self::Sha384 => 48,
+            Self::Sha512 => 64,
+        }
+    }
+
+    /// Hash `data`.
+    pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha256 => Sha256::digest(data).to_vec(),
+            Self::Sha384 => Sha384::digest(data).to_vec(),
+            Self::Sha512 => Sha512::digest(data).to_vec(),
+            Self::Blake3 => blake3::hash(data).as_bytes().to_vec(),
+        }
+    }
+
+    /// Start hashing data that arrives in pieces.
+    pub fn hasher(&self) -> Hasher {
+        match self {
+            Self::Sha256 => Hasher::Sha256(Sha256::new()),
+            Self::Sha384 => Hasher::Sha384(Sha384::new()),
+            Self::Sha512 => Hasher::Sha512(Sha512::new()),
+            Self::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+}
+
+/// Incremental hash state; [`Hasher::finalize`] gives the same digest as
+/// [`HashAlg::digest`] over everything passed to [`Hasher::update`].
+#[derive(Clone)]
+pub enum Hasher {
+    Sha256(Sha256),
+    Sha384(Sha384),
+    Sha512(Sha512),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(h) => h.update(data),
+            Self::Sha384(h) => h.update(data),
+            Self::Sha512(h) => h.update(data),
+            Self::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        match self {
+            Self::Sha256(h) => h.finalize().to_vec(),
+            Self::Sha384(h) => h.finalize().to_vec(),
+            Self::Sha512(h) => h.finalize().to_vec(),
+            Self::Blake3(h) => h.finalize().as_bytes().to_vec(),
+        }
+    }
+}
+
+impl std::str::FromStr for HashAlg {
+    type Err = TransformError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sha256" => Ok(Self::Sha256),
+            "sha384" => Ok(Self::Sha384),
+            "sha512" => Ok(Self::Sha512),
+            "blake3" => Ok(Self::Blake3),
+            other => Err(TransformError::C2pa(format!("unknown hash algorithm: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [HashAlg; 4] = [HashAlg::Sha256, HashAlg::Sha384, HashAlg::Sha512, HashAlg::Blake3];
+
+    #[test]
+    fn test_digest_lengths_and_names() {
+        for alg in ALL {
+            assert_eq!(alg.digest(b"abc").len(), alg.output_len());
+            assert_eq!(alg.as_str().parse::<HashAlg>().unwrap(), alg);
+        }
+        assert!("md5".parse::<HashAlg>().is_err());
+    }
+
+    #[test]
+    fn test_hasher_matches_digest() {
+        for alg in ALL {
+            let mut hasher = alg.hasher();
+            hasher.update(b"a");
+            hasher.update(b"bc");
+            assert_eq!(hasher.finalize(), alg.digest(b"abc"));
+        }
+    }
+
+    #[test]
+    fn test_algorithms_differ() {
+        let digests: Vec<_> = ALL.iter().map(|alg| alg.digest(b"abc")).collect();
+        assert_ne!(digests[0], digests[3]);
+        assert_eq!(digests[0], Sha256::digest(b"abc").to_vec());
+    }
+}
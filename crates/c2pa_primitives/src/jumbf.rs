@@ -0,0 +1,583 @@
+//! JUMBF (ISO/IEC 19566-5) manifest stores.
+//!
+//! A [`ManifestStore`] serializes to the box layout C2PA uses, so every
+//! manifest ID labels a box and resolves through its JUMBF URI
+//! (`self#jumbf=c2pa/<manifest_id>/c2pa.claim`):
+//!
+//! ```text
+//! jumb "c2pa"                          ; manifest store
+//! └─ jumb "<manifest_id>"              ; one per manifest, active last
+//!    ├─ jumb "c2pa.assertions"         ; assertion store
+//!    │  ├─ jumb "c2pa.hash.data"       ; cbor: binding
+//!    │  ├─ jumb "c2pa.ingredient"      ; cbor: ingredient; "__1", "__2", ... for the rest
+//!    │  └─ jumb "<label>"              ; json, or cbor { "mime_type": tstr, "data": bstr }
+//!    ├─ jumb "c2pa.claim"              ; cbor: claim, see `claim` module
+//!    └─ jumb "c2pa.signature"          ; cbor: COSE_Sign1 with detached payload
+//! ```
+//!
+//! Every superbox starts with a `jumd` description box carrying its C2PA
+//! content type and label. The claim box holds the canonical claim, plus an
+//! `"alg"` entry for a claim hash other than SHA-256; the claim hash itself
+//! is recomputed on load. The assertion store repeats what the claim records
+//! so that each assertion has its own URI; a store whose assertion boxes
+//! disagree with the claim is rejected.
+//!
+//! Only claims signed through a [`CoseSign1`] envelope can be stored.
+//! Loading always yields unverified provenance.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::cbor::Value;
+use crate::claim;
+use crate::{
+    AssetBinding, C2pa, ClaimHash, CoseSign1, CustomAssertion, IngredientRef, Provenance,
+    TransformError, Unverified,
+};
+
+/// C2PA content type UUID for a four-character code.
+const fn c2pa_uuid(code: &[u8; 4]) -> [u8; 16] {
+    [
+        code[0], code[1], code[2], code[3], 0x00, 0x11, 0x00, 0x10, 0x80, 0x00, 0x00, 0xaa, 0x00,
+        0x38, 0x9b, 0x71,
+    ]
+}
+
+const STORE_TYPE: [u8; 16] = c2pa_uuid(b"c2pa");
+const MANIFEST_TYPE: [u8; 16] = c2pa_uuid(b"c2ma");
+const ASSERTIONS_TYPE: [u8; 16] = c2pa_uuid(b"c2as");
+const CLAIM_TYPE: [u8; 16] = c2pa_uuid(b"c2cl");
+const SIGNATURE_TYPE: [u8; 16] = c2pa_uuid(b"c2cs");
+const CBOR_TYPE: [u8; 16] = c2pa_uuid(b"cbor");
+const JSON_TYPE: [u8; 16] = c2pa_uuid(b"json");
+
+const SUPERBOX: [u8; 4] = *b"jumb";
+const DESCRIPTION: [u8; 4] = *b"jumd";
+const CBOR_BOX: [u8; 4] = *b"cbor";
+const JSON_BOX: [u8; 4] = *b"json";
+
+/// Description toggles: requestable, label present.
+const TOGGLES: u8 = 0x03;
+const LABEL_PRESENT: u8 = 0x02;
+
+const STORE_LABEL: &str = "c2pa";
+const ASSERTIONS_LABEL: &str = "c2pa.assertions";
+const CLAIM_LABEL: &str = "c2pa.claim";
+const SIGNATURE_LABEL: &str = "c2pa.signature";
+const BINDING_LABEL: &str = "c2pa.hash.data";
+const INGREDIENT_LABEL: &str = "c2pa.ingredient";
+
+/// Prefix of a URI into the asset's own manifest store.
+const URI_PREFIX: &str = "self#jumbf=";
+
+fn malformed(what: &str) -> TransformError {
+    TransformError::Manifest(format!("invalid JUMBF: {}", what))
+}
+
+/// Manifests serialized as a C2PA manifest store.
+#[derive(Debug, Clone, Default)]
+pub struct ManifestStore {
+    /// Manifests in store order; the last one is active.
+    pub manifests: Vec<Provenance>,
+}
+
+impl ManifestStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A store holding the manifest of `value`.
+    pub fn from_value<T, S>(value: &C2pa<T, S>) -> Self {
+        Self::new().with_manifest(value.provenance().clone())
+    }
+
+    /// Add a manifest, making it the active one.
+    pub fn with_manifest(mut self, provenance: Provenance) -> Self {
+        self.manifests.push(provenance);
+        self
+    }
+
+    /// The active manifest.
+    pub fn active(&self) -> Option<&Provenance> {
+        self.manifests.last()
+    }
+
+    /// The manifest labelled `manifest_id`.
+    pub fn get(&self, manifest_id: &str) -> Option<&Provenance> {
+        self.manifests.iter().find(|p| p.manifest_id == manifest_id)
+    }
+
+    /// Pair the active manifest with a payload supplied by the caller.
+    pub fn into_unverified<T>(mut self, payload: T) -> Result<C2pa<T, Unverified>, TransformError> {
+        let provenance = self
+            .manifests
+            .pop()
+            .ok_or_else(|| TransformError::Manifest("manifest store is empty".into()))?;
+        Ok(C2pa::new(payload, provenance))
+    }
+
+    /// JUMBF URI of the manifest box labelled `manifest_id`.
+    pub fn manifest_uri(manifest_id: &str) -> String {
+        format!("{}{}/{}", URI_PREFIX, STORE_LABEL, manifest_id)
+    }
+
+    /// Serialize to a `c2pa` superbox.
+    ///
+    /// Fails for a manifest whose claim hash does not match its claim, or
+    /// whose claim was not signed through a COSE_Sign1 envelope.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, TransformError> {
+        let manifests = self
+            .manifests
+            .iter()
+            .map(manifest_box)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(superbox(STORE_TYPE, STORE_LABEL, manifests))
+    }
+
+    /// Parse a `c2pa` superbox.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, TransformError> {
+        let manifests = store_box(data)?
+            .superboxes()?
+            .into_iter()
+            .map(|manifest| decode_manifest(data, manifest))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { manifests })
+    }
+
+    /// Byte range of the box that `uri` names within the serialized store
+    /// `data`, e.g. `self#jumbf=c2pa/<manifest_id>/c2pa.assertions`.
+    pub fn locate(data: &[u8], uri: &str) -> Result<Range<usize>, TransformError> {
+        let path = uri
+            .strip_prefix(URI_PREFIX)
+            .ok_or_else(|| TransformError::Manifest(format!("not a JUMBF URI: {}", uri)))?;
+        let not_found = || TransformError::Manifest(format!("no box at {}", uri));
+
+        let mut labels = path.strip_prefix('/').unwrap_or(path).split('/');
+        let mut current = store_box(data)?;
+        if labels.next() != Some(current.label.as_str()) {
+            return Err(not_found());
+        }
+        for label in labels {
+            current = current
+                .superboxes()?
+                .into_iter()
+                .find(|child| child.label == label)
+                .ok_or_else(not_found)?;
+        }
+        Ok(current.range)
+    }
+}
+
+// ============================================================================
+// Writing
+// ============================================================================
+
+fn write_box(box_type: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len() + 16);
+    match u32::try_from(body.len() + 8) {
+        Ok(len) => {
+            out.extend_from_slice(&len.to_be_bytes());
+            out.extend_from_slice(box_type);
+        }
+        // LBox of 1 means the length follows as a 64-bit XLBox
+        Err(_) => {
+            out.extend_from_slice(&1u32.to_be_bytes());
+            out.extend_from_slice(box_type);
+            out.extend_from_slice(&(body.len() as u64 + 16).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(body);
+    out
+}
+
+fn superbox(content_type: [u8; 16], label: &str, children: Vec<Vec<u8>>) -> Vec<u8> {
+    let mut description = content_type.to_vec();
+    description.push(TOGGLES);
+    description.extend_from_slice(label.as_bytes());
+    description.push(0);
+
+    let mut body = write_box(&DESCRIPTION, &description);
+    for child in children {
+        body.extend(child);
+    }
+    write_box(&SUPERBOX, &body)
+}
+
+fn cbor_box(label: &str, cbor: Vec<u8>) -> Vec<u8> {
+    superbox(CBOR_TYPE, label, vec![write_box(&CBOR_BOX, &cbor)])
+}
+
+/// Labels end up in URIs, so they cannot contain the path separator.
+fn check_label(label: &str) -> Result<(), TransformError> {
+    if label.is_empty() || label.contains(['/', '\0']) {
+        return Err(TransformError::Manifest(format!("`{}` is not a valid JUMBF label", label)));
+    }
+    Ok(())
+}
+
+/// `label`, with an instance suffix if it was used before in the same store.
+fn instance_label(seen: &mut HashMap<String, usize>, label: &str) -> String {
+    let count = seen.entry(label.to_string()).or_insert(0);
+    let instance = match *count {
+        0 => label.to_string(),
+        n => format!("{}__{}", label, n),
+    };
+    *count += 1;
+    instance
+}
+
+fn assertion_store(
+    binding: &AssetBinding,
+    ingredients: &[IngredientRef],
+    assertions: &[CustomAssertion],
+) -> Result<Vec<u8>, TransformError> {
+    let mut seen = HashMap::new();
+    let mut boxes = vec![cbor_box(
+        &instance_label(&mut seen, BINDING_LABEL),
+        claim::binding_value(binding).to_bytes(),
+    )];
+    for ingredient in ingredients {
+        boxes.push(cbor_box(
+            &instance_label(&mut seen, INGREDIENT_LABEL),
+            claim::ingredient_value(ingredient).to_bytes(),
+        ));
+    }
+    for assertion in assertions {
+        check_label(&assertion.label)?;
+        let label = instance_label(&mut seen, &assertion.label);
+        boxes.push(if assertion.mime_type == "application/json" {
+            superbox(JSON_TYPE, &label, vec![write_box(&JSON_BOX, &assertion.data)])
+        } else {
+            let value = Value::map([
+                ("mime_type", Value::text(&assertion.mime_type)),
+                ("data", Value::bytes(assertion.data.clone())),
+            ]);
+            cbor_box(&label, value.to_bytes())
+        });
+    }
+    Ok(superbox(ASSERTIONS_TYPE, ASSERTIONS_LABEL, boxes))
+}
+
+fn manifest_box(provenance: &Provenance) -> Result<Vec<u8>, TransformError> {
+    check_label(&provenance.manifest_id)?;
+    let claim_bytes = provenance.claim_bytes();
+    if ClaimHash::compute(provenance.claim_hash.alg(), &claim_bytes) != provenance.claim_hash {
+        return Err(TransformError::Manifest(format!(
+            "claim hash of {} does not match its claim",
+            provenance.manifest_id
+        )));
+    }
+
+    let claim = claim::claim_value(
+        &provenance.generator,
+        &provenance.asset_binding,
+        &provenance.ingredients,
+        &provenance.assertions,
+    );
+    let mut children = vec![
+        assertion_store(
+            &provenance.asset_binding,
+            &provenance.ingredients,
+            &provenance.assertions,
+        )?,
+        superbox(
+            CLAIM_TYPE,
+            CLAIM_LABEL,
+            vec![write_box(
+                &CBOR_BOX,
+                &claim::with_hash_alg(claim, provenance.claim_hash.alg()).to_bytes(),
+            )],
+        ),
+    ];
+    if provenance.signature.is_some() {
+        let envelope = CoseSign1::from_provenance(provenance)?.detached();
+        children.push(superbox(
+            SIGNATURE_TYPE,
+            SIGNATURE_LABEL,
+            vec![write_box(&CBOR_BOX, &envelope.to_bytes())],
+        ));
+    }
+    Ok(superbox(MANIFEST_TYPE, &provenance.manifest_id, children))
+}
+
+// ============================================================================
+// Reading
+// ============================================================================
+
+/// A box within a buffer, with its range in the outermost buffer.
+struct RawBox<'a> {
+    box_type: [u8; 4],
+    range: Range<usize>,
+    body: &'a [u8],
+    body_start: usize,
+}
+
+/// Split `data`, which starts at `base` in the outermost buffer, into boxes.
+fn read_boxes(data: &[u8], base: usize) -> Result<Vec<RawBox<'_>>, TransformError> {
+    let mut boxes = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let header = data
+            .get(pos..pos + 8)
+            .ok_or_else(|| malformed("truncated box header"))?;
+        let box_type: [u8; 4] = header[4..].try_into().expect("four bytes");
+        let remaining = (data.len() - pos) as u64;
+        let lbox = u32::from_be_bytes(header[..4].try_into().expect("four bytes"));
+        let (header_len, len) = match lbox {
+            0 => (8, remaining),
+            1 => {
+                let xl = data
+                    .get(pos + 8..pos + 16)
+                    .ok_or_else(|| malformed("truncated box header"))?;
+                (16, u64::from_be_bytes(xl.try_into().expect("eight bytes")))
+            }
+            len => (8, u64::from(len)),
+        };
+        if len < header_len as u64 || len > remaining {
+            return Err(malformed("box length"));
+        }
+        let end = pos + len as usize;
+        boxes.push(RawBox {
+            box_type,
+            range: base + pos..base + end,
+            body: &data[pos + header_len..end],
+            body_start: base + pos + header_len,
+        });
+        pos = end;
+    }
+    Ok(boxes)
+}
+
+/// A parsed superbox: its description and the boxes after it.
+struct Superbox<'a> {
+    content_type: [u8; 16],
+    label: String,
+    range: Range<usize>,
+    children: Vec<RawBox<'a>>,
+}
+
+impl<'a> Superbox<'a> {
+    fn parse(raw: RawBox<'a>) -> Result<Self, TransformError> {
+        if raw.box_type != SUPERBOX {
+            return Err(malformed("expected a superbox"));
+        }
+        let mut boxes = read_boxes(raw.body, raw.body_start)?.into_iter();
+        let description = boxes
+            .next()
+            .filter(|b| b.box_type == DESCRIPTION)
+            .ok_or_else(|| malformed("superbox has no description"))?;
+        let (content_type, rest) = description
+            .body
+            .split_first_chunk::<16>()
+            .ok_or_else(|| malformed("description"))?;
+        let label = match rest.split_first() {
+            Some((toggles, rest)) if toggles & LABEL_PRESENT != 0 => {
+                let end = rest
+                    .iter()
+                    .position(|&b| b == 0)
+                    .ok_or_else(|| malformed("unterminated label"))?;
+                String::from_utf8(rest[..end].to_vec()).map_err(|_| malformed("label"))?
+            }
+            Some(_) => String::new(),
+            None => return Err(malformed("description")),
+        };
+        Ok(Self {
+            content_type: *content_type,
+            label,
+            range: raw.range,
+            children: boxes.collect(),
+        })
+    }
+
+    /// Nested superboxes, skipping content boxes.
+    fn superboxes(self) -> Result<Vec<Superbox<'a>>, TransformError> {
+        self.children
+            .into_iter()
+            .filter(|b| b.box_type == SUPERBOX)
+            .map(Superbox::parse)
+            .collect()
+    }
+
+    /// Body of the content box of type `box_type`.
+    fn content(&self, box_type: &[u8; 4]) -> Result<&'a [u8], TransformError> {
+        self.children
+            .iter()
+            .find(|b| &b.box_type == box_type)
+            .map(|b| b.body)
+            .ok_or_else(|| malformed(&format!("`{}` has no content box", self.label)))
+    }
+}
+
+fn store_box(data: &[u8]) -> Result<Superbox<'_>, TransformError> {
+    let raw = match <[RawBox; 1]>::try_from(read_boxes(data, 0)?) {
+        Ok([raw]) => raw,
+        Err(_) => return Err(malformed("expected a single manifest store box")),
+    };
+    let store = Superbox::parse(raw)?;
+    if store.content_type != STORE_TYPE || store.label != STORE_LABEL {
+        return Err(malformed("not a C2PA manifest store"));
+    }
+    Ok(store)
+}
+
+fn decode_manifest(data: &[u8], manifest: Superbox) -> Result<Provenance, TransformError> {
+    if manifest.content_type != MANIFEST_TYPE {
+        return Err(malformed("not a C2PA manifest"));
+    }
+    let manifest_id = manifest.label.clone();
+    let parts = manifest.superboxes()?;
+    let find = |content_type: [u8; 16], label: &str| {
+        parts
+            .iter()
+            .find(|b| b.content_type == content_type && b.label == label)
+    };
+
+    let claim_box =
+        find(CLAIM_TYPE, CLAIM_LABEL).ok_or_else(|| malformed("manifest has no claim"))?;
+    let claim = Value::from_bytes(claim_box.content(&CBOR_BOX)?).map_err(|e| malformed(e.0))?;
+    let alg = claim::hash_alg(&claim)?;
+    let claim::ClaimParts {
+        generator,
+        binding,
+        ingredients,
+        assertions,
+    } = claim::decode_claim(&claim)?;
+
+    // The assertion store must hold exactly what the claim records
+    let assertions_box = find(ASSERTIONS_TYPE, ASSERTIONS_LABEL)
+        .ok_or_else(|| malformed("manifest has no assertion store"))?;
+    let expected = assertion_store(&binding, &ingredients, &assertions)?;
+    if data[assertions_box.range.clone()] != expected[..] {
+        return Err(malformed("assertion store does not match the claim"));
+    }
+
+    let claim_bytes = claim::encode_claim(&generator, &binding, &ingredients, &assertions);
+    let signature = match find(SIGNATURE_TYPE, SIGNATURE_LABEL) {
+        Some(signature_box) => {
+            let envelope = CoseSign1::from_bytes(signature_box.content(&CBOR_BOX)?)?;
+            if envelope.payload.as_ref().is_some_and(|payload| *payload != claim_bytes) {
+                return Err(malformed("signature payload is not the claim"));
+            }
+            Some(envelope.to_claim_signature())
+        }
+        None => None,
+    };
+
+    Ok(Provenance {
+        manifest_id,
+        claim_hash: ClaimHash::compute(alg, &claim_bytes),
+        asset_binding: binding,
+        ingredients,
+        generator,
+        assertions,
+        signature,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_signer;
+    use crate::*;
+
+    fn signed_invoice(
+        signer: &Ed25519Signer,
+    ) -> (C2pa<Vec<u8>, Verified>, C2pa<Invoice, Verified>) {
+        let source = C2paBuilder::new(b"7:250".to_vec())
+            .cose_sign1(true)
+            .sign(signer)
+            .unwrap();
+        let invoice = C2paBuilder::new(Invoice { id: 7, amount: 250 })
+            .generator("billing/1.0")
+            .hash_alg(HashAlg::Sha384)
+            .cose_sign1(true)
+            .add_ingredient(&source, IngredientRelation::DerivedFrom)
+            .add_ingredient(&source, IngredientRelation::ComponentOf)
+            .add_assertion(CustomAssertion::json("c2pa.test", r#"{"ok":true}"#))
+            .add_assertion(CustomAssertion {
+                label: "c2pa.thumbnail".into(),
+                data: vec![0x89, b'P', b'N', b'G'],
+                mime_type: "image/png".into(),
+            })
+            .sign(signer)
+            .unwrap();
+        (source, invoice)
+    }
+
+    #[test]
+    fn test_store_roundtrip_then_verify() {
+        let signer = test_signer(5);
+        let (source, invoice) = signed_invoice(&signer);
+        let store = ManifestStore::new()
+            .with_manifest(source.provenance().clone())
+            .with_manifest(invoice.provenance().clone());
+        let loaded = ManifestStore::from_bytes(&store.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(loaded.manifests.len(), 2);
+        let source_id = &source.provenance().manifest_id;
+        assert_eq!(loaded.get(source_id).unwrap().claim_hash, source.provenance().claim_hash);
+
+        let active = loaded.active().unwrap();
+        assert_eq!(active.claim_hash, invoice.provenance().claim_hash);
+        assert_eq!(active.ingredients.len(), 2);
+        assert_eq!(active.assertions[1].mime_type, "image/png");
+        assert_eq!(active.assertions[1].data, invoice.provenance().assertions[1].data);
+
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+        let loaded = loaded.into_unverified(invoice.payload().clone()).unwrap();
+        assert!(verify(loaded, &trusted).is_ok());
+    }
+
+    #[test]
+    fn test_uris_resolve_to_boxes() {
+        let (_, invoice) = signed_invoice(&test_signer(5));
+        let bytes = ManifestStore::from_value(&invoice).to_bytes().unwrap();
+        let manifest_uri = ManifestStore::manifest_uri(&invoice.provenance().manifest_id);
+
+        let manifest = ManifestStore::locate(&bytes, &manifest_uri).unwrap();
+        assert_eq!(&bytes[manifest.start + 4..manifest.start + 8], b"jumb");
+        for path in [
+            "c2pa.claim",
+            "c2pa.signature",
+            "c2pa.assertions/c2pa.hash.data",
+            "c2pa.assertions/c2pa.ingredient__1",
+            "c2pa.assertions/c2pa.thumbnail",
+        ] {
+            let range = ManifestStore::locate(&bytes, &format!("{}/{}", manifest_uri, path))
+                .unwrap();
+            assert!(manifest.start < range.start && range.end <= manifest.end, "{}", path);
+        }
+
+        let missing = format!("{}/c2pa.assertions/c2pa.ingredient__2", manifest_uri);
+        assert!(ManifestStore::locate(&bytes, &missing).is_err());
+        assert!(ManifestStore::locate(&bytes, "c2pa/c2pa.claim").is_err());
+    }
+
+    #[test]
+    fn test_rejects_unstorable_manifests() {
+        let signer = test_signer(5);
+        let plain = C2paBuilder::new(3u32).sign(&signer).unwrap();
+        assert!(ManifestStore::from_value(&plain).to_bytes().is_err());
+
+        let mut provenance = signed_invoice(&signer).1.provenance().clone();
+        provenance.assertions[0].label = "a/b".into();
+        assert!(ManifestStore::new().with_manifest(provenance).to_bytes().is_err());
+    }
+
+    #[test]
+    fn test_rejects_tampered_or_corrupt_stores() {
+        let (_, invoice) = signed_invoice(&test_signer(5));
+        let bytes = ManifestStore::from_value(&invoice).to_bytes().unwrap();
+
+        // An assertion box edited without touching the claim
+        let mut tampered = bytes.clone();
+        let at = tampered.windows(4).position(|w| w == b"true").unwrap();
+        tampered[at] = b'T';
+        assert!(ManifestStore::from_bytes(&tampered).is_err());
+
+        assert!(ManifestStore::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(ManifestStore::from_bytes(b"").is_err());
+        let foreign = superbox(CBOR_TYPE, STORE_LABEL, Vec::new());
+        assert!(ManifestStore::from_bytes(&foreign).is_err());
+    }
+}
@@ -21,11 +21,50 @@
 //! ```
 
 use sha2::{Digest, Sha256};
+use std::io::Read;
 use std::marker::PhantomData;
+use std::sync::Arc;
 use thiserror::Error;
 
+// Lets the macros' `c2pa_primitives::` paths resolve inside this crate
+extern crate self as c2pa_primitives;
+
 // Re-export the attribute macros
-pub use c2pa_macros::{c2pa_pipeline, c2pa_source, c2pa_transform};
+pub use c2pa_macros::{
+    c2pa_composite, c2pa_pipeline, c2pa_source, c2pa_transform, C2paBindable, C2paCommit,
+};
+
+mod cbor;
+mod cert;
+mod chain;
+mod claim;
+mod commit;
+mod cose;
+mod hash;
+mod jumbf;
+mod manifest;
+mod parallel;
+mod png;
+mod signers;
+mod timestamp;
+mod validation;
+pub use cert::{Certificate, CertificateBuilder, KeyUsage, TrustStore};
+pub use chain::ChainVerifier;
+pub use claim::CLAIM_FORMAT_VERSION;
+pub use commit::{commit_fields, commit_variant, recorded_commitments, C2paCommit, ParamOpening};
+pub use cose::{CoseSign1, COSE_SIGN1_TAG};
+pub use hash::{HashAlg, Hasher};
+pub use jumbf::ManifestStore;
+pub use manifest::{C2paPayload, Manifest, MANIFEST_FORMAT, MANIFEST_FORMAT_VERSION};
+pub use parallel::{par_map, PipelineHandle};
+pub use png::{embed_png, extract_png, png_binding};
+pub use signers::{
+    Ed25519Signer, Ed25519Verifier, P256Signer, P256Verifier, RsaPssSigner, RsaPssVerifier,
+};
+pub use timestamp::{LocalTimestampAuthority, TimestampAuthority, TimestampToken};
+pub use validation::{ManifestStatus, StatusCode, Trust, ValidationReport, ValidationStatus};
+
+use claim::encode_claim;
 
 // ============================================================================
 // Marker Types - Type-level state encoding
@@ -113,14 +152,21 @@ impl<T> C2pa<T, Verified> {
 /// Provenance metadata linking a value to its C2PA manifest.
 #[derive(Debug, Clone)]
 pub struct Provenance {
-    /// Active manifest identifier (JUMBF URI).
+    /// Active manifest identifier; labels the manifest's JUMBF box
+    /// (see [`ManifestStore::manifest_uri`]).
     pub manifest_id: String,
-    /// SHA-256 hash of the claim.
+    /// Hash of the claim.
     pub claim_hash: ClaimHash,
     /// How the asset is bound to the manifest.
     pub asset_binding: AssetBinding,
     /// Parent references (for transformed assets).
     pub ingredients: Vec<IngredientRef>,
+    /// Generator label recorded in the claim.
+    pub generator: String,
+    /// Assertions recorded in the claim.
+    pub assertions: Vec<CustomAssertion>,
+    /// Signature over the claim and the identity that produced it.
+    pub signature: Option<ClaimSignature>,
 }
 
 impl Provenance {
@@ -131,6 +177,9 @@ impl Provenance {
             claim_hash,
             asset_binding: binding,
             ingredients: Vec::new(),
+            generator: String::new(),
+            assertions: Vec::new(),
+            signature: None,
         }
     }
 
@@ -146,47 +195,298 @@ impl Provenance {
             claim_hash,
             asset_binding: binding,
             ingredients,
+            generator: String::new(),
+            assertions: Vec::new(),
+            signature: None,
+        }
+    }
+
+    /// Set the generator label recorded in the claim.
+    pub fn with_generator(mut self, generator: impl Into<String>) -> Self {
+        self.generator = generator.into();
+        self
+    }
+
+    /// Set the assertions recorded in the claim.
+    pub fn with_assertions(mut self, assertions: Vec<CustomAssertion>) -> Self {
+        self.assertions = assertions;
+        self
+    }
+
+    /// Attach the claim signature.
+    pub fn with_signature(mut self, signature: ClaimSignature) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
+    /// Rebuild the claim bytes from the provenance contents.
+    ///
+    /// These are the bytes the claim hash commits to and the signature covers.
+    pub fn claim_bytes(&self) -> Vec<u8> {
+        encode_claim(
+            &self.generator,
+            &self.asset_binding,
+            &self.ingredients,
+            &self.assertions,
+        )
+    }
+}
+
+/// Signature block stored alongside the claim.
+///
+/// Carries everything a consumer needs to re-check the signature
+/// without access to the process that produced it.
+#[derive(Debug, Clone)]
+pub struct ClaimSignature {
+    /// Algorithm used to produce `signature`.
+    pub algorithm: SigningAlg,
+    /// Raw signature bytes over the claim, or over its COSE
+    /// `Sig_structure` if `protected_header` is set.
+    pub signature: Vec<u8>,
+    /// Identifier of the signing key (see [`Signer::key_id`]).
+    pub key_id: Vec<u8>,
+    /// Signer certificate chain, leaf first. May be empty.
+    pub certificate_chain: Vec<Vec<u8>>,
+    /// Trusted timestamp over `signature`, if one was obtained.
+    pub timestamp: Option<TimestampToken>,
+    /// Serialized COSE protected header if the claim was signed through a
+    /// COSE_Sign1 envelope (see [`CoseSign1`]).
+    pub protected_header: Option<Vec<u8>>,
+}
+
+impl ClaimSignature {
+    /// The bytes `signature` covers for a claim serialized as `claim_bytes`.
+    ///
+    /// Fails if the protected header disagrees with the algorithm, key or
+    /// certificate chain recorded here.
+    pub fn signed_bytes(&self, claim_bytes: &[u8]) -> Result<Vec<u8>, TransformError> {
+        let Some(protected) = &self.protected_header else {
+            return Ok(claim_bytes.to_vec());
+        };
+        let headers = cose::Headers::decode(protected)?;
+        if headers.alg != Some(self.algorithm)
+            || headers.kid.is_some_and(|kid| kid != self.key_id)
+            || headers.x5chain.is_some_and(|chain| chain != self.certificate_chain)
+        {
+            return Err(TransformError::Verification(
+                "protected header does not match the claim signature".into(),
+            ));
         }
+        Ok(cose::sig_structure(protected, claim_bytes))
     }
 }
 
-/// SHA-256 claim hash.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ClaimHash(pub [u8; 32]);
+/// Claim signature algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningAlg {
+    /// Placeholder signature from [`TestSigner`]; never verifiable.
+    Placeholder,
+    /// EdDSA over Curve25519.
+    Ed25519,
+    /// ECDSA over P-256 with SHA-256.
+    Es256,
+    /// RSASSA-PSS with SHA-256.
+    Ps256,
+}
+
+impl SigningAlg {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Placeholder => "placeholder",
+            Self::Ed25519 => "Ed25519",
+            Self::Es256 => "ES256",
+            Self::Ps256 => "PS256",
+        }
+    }
+}
+
+impl std::str::FromStr for SigningAlg {
+    type Err = TransformError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "placeholder" => Ok(Self::Placeholder),
+            "Ed25519" => Ok(Self::Ed25519),
+            "ES256" => Ok(Self::Es256),
+            "PS256" => Ok(Self::Ps256),
+            other => Err(TransformError::C2pa(format!("unknown signing algorithm: {}", other))),
+        }
+    }
+}
+
+/// Hash of a claim, with the algorithm that produced it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClaimHash {
+    alg: HashAlg,
+    digest: Vec<u8>,
+}
 
 impl ClaimHash {
+    /// A SHA-256 claim hash.
     pub fn from_bytes(bytes: [u8; 32]) -> Self {
-        Self(bytes)
+        Self {
+            alg: HashAlg::Sha256,
+            digest: bytes.to_vec(),
+        }
     }
 
-    pub fn as_bytes(&self) -> &[u8; 32] {
-        &self.0
+    /// A claim hash produced by `alg`; fails if the length does not match.
+    pub fn new(alg: HashAlg, digest: Vec<u8>) -> Result<Self, TransformError> {
+        check_digest_len(alg, &digest)?;
+        Ok(Self { alg, digest })
+    }
+
+    /// Hash the canonical claim bytes.
+    pub fn compute(alg: HashAlg, claim_bytes: &[u8]) -> Self {
+        Self {
+            alg,
+            digest: alg.digest(claim_bytes),
+        }
+    }
+
+    pub fn alg(&self) -> HashAlg {
+        self.alg
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.digest
     }
 }
 
 /// How an asset is bound to its manifest.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AssetBinding {
     /// Hash-based binding (most common).
     Hash(ContentHash),
-    /// Box-based binding with offset/length (for embedded data).
+    /// Hash of the payload bytes with the `length` bytes at `offset` left
+    /// out, so the manifest can be embedded in that region.
     Box { offset: u64, length: u64, hash: ContentHash },
 }
 
-/// SHA-256 content hash.
+/// Hash of an asset's content, with the algorithm that produced it.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ContentHash(pub [u8; 32]);
+pub struct ContentHash {
+    alg: HashAlg,
+    digest: Vec<u8>,
+}
 
 impl ContentHash {
+    /// A SHA-256 content hash.
     pub fn from_bytes(bytes: [u8; 32]) -> Self {
-        Self(bytes)
+        Self {
+            alg: HashAlg::Sha256,
+            digest: bytes.to_vec(),
+        }
+    }
+
+    /// A content hash produced by `alg`; fails if the length does not match.
+    pub fn new(alg: HashAlg, digest: Vec<u8>) -> Result<Self, TransformError> {
+        check_digest_len(alg, &digest)?;
+        Ok(Self { alg, digest })
     }
 
+    /// SHA-256 of `data`.
     pub fn compute<T: AsRef<[u8]>>(data: T) -> Self {
-        let mut hasher = Sha256::new();
-        hasher.update(data.as_ref());
-        Self(hasher.finalize().into())
+        Self::compute_with(HashAlg::Sha256, data)
+    }
+
+    /// Hash `data` with `alg`.
+    pub fn compute_with<T: AsRef<[u8]>>(alg: HashAlg, data: T) -> Self {
+        Self {
+            alg,
+            digest: alg.digest(data.as_ref()),
+        }
+    }
+
+    /// Hash `data` with `alg`, leaving out the `length` bytes at `offset`.
+    ///
+    /// Fails unless the excluded range lies within `data`.
+    pub fn compute_excluding(
+        alg: HashAlg,
+        data: &[u8],
+        offset: u64,
+        length: u64,
+    ) -> Result<Self, TransformError> {
+        Self::from_reader_excluding(alg, data, offset, length)
+    }
+
+    /// Hash a byte stream with `alg`, leaving out the `length` bytes at
+    /// `offset` (e.g. the region holding an embedded manifest).
+    ///
+    /// Fails unless the excluded range lies within the stream.
+    pub fn from_reader_excluding(
+        alg: HashAlg,
+        mut reader: impl Read,
+        offset: u64,
+        length: u64,
+    ) -> Result<Self, TransformError> {
+        let end = offset
+            .checked_add(length)
+            .ok_or_else(|| TransformError::Binding("excluded range overflows".into()))?;
+        let mut hasher = alg.hasher();
+        let mut buf = [0u8; 8192];
+        let mut pos = 0u64;
+        loop {
+            let n = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    return Err(TransformError::Binding(format!("reading content: {}", err)))
+                }
+            };
+            let chunk_end = pos + n as u64;
+            // Hash what lies before the exclusion, then what lies after it
+            let before = offset.clamp(pos, chunk_end) - pos;
+            let after = end.clamp(pos, chunk_end) - pos;
+            hasher.update(&buf[..before as usize]);
+            hasher.update(&buf[after as usize..n]);
+            pos = chunk_end;
+        }
+        if pos < end {
+            return Err(TransformError::Binding(format!(
+                "excluded range {}..{} ends past the content ({} bytes)",
+                offset, end, pos
+            )));
+        }
+        Ok(Self {
+            alg,
+            digest: hasher.finalize(),
+        })
+    }
+
+    /// Combine named field hashes into one content hash.
+    ///
+    /// The fields are encoded as a deterministic CBOR map of name to hash,
+    /// so the result is independent of field order. Used by
+    /// `#[derive(C2paBindable)]`.
+    pub fn from_fields(alg: HashAlg, fields: &[(&str, ContentHash)]) -> Self {
+        let entries = fields
+            .iter()
+            .map(|(name, hash)| (cbor::Value::text(*name), cbor::Value::bytes(hash.as_bytes())))
+            .collect();
+        Self::compute_with(alg, cbor::Value::Map(entries).to_bytes())
+    }
+
+    pub fn alg(&self) -> HashAlg {
+        self.alg
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.digest
+    }
+}
+
+fn check_digest_len(alg: HashAlg, digest: &[u8]) -> Result<(), TransformError> {
+    if digest.len() != alg.output_len() {
+        return Err(TransformError::C2pa(format!(
+            "{} digest must be {} bytes, found {}",
+            alg.as_str(),
+            alg.output_len(),
+            digest.len()
+        )));
     }
+    Ok(())
 }
 
 /// Reference to a parent ingredient.
@@ -200,6 +500,17 @@ pub struct IngredientRef {
     pub relationship: IngredientRelation,
 }
 
+impl IngredientRef {
+    /// Reference a verified value as an ingredient.
+    pub fn from_verified<T>(ingredient: &C2pa<T, Verified>, relationship: IngredientRelation) -> Self {
+        Self {
+            claim_hash: ingredient.provenance.claim_hash.clone(),
+            asset_binding: ingredient.provenance.asset_binding.clone(),
+            relationship,
+        }
+    }
+}
+
 /// C2PA-defined ingredient relationships.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IngredientRelation {
@@ -224,16 +535,49 @@ impl IngredientRelation {
     }
 }
 
+impl std::str::FromStr for IngredientRelation {
+    type Err = TransformError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "parentOf" => Ok(Self::ParentOf),
+            "componentOf" => Ok(Self::ComponentOf),
+            "inputTo" => Ok(Self::InputTo),
+            "derivedFrom" => Ok(Self::DerivedFrom),
+            "composedFrom" => Ok(Self::ComposedFrom),
+            other => Err(TransformError::C2pa(format!("unknown relationship: {}", other))),
+        }
+    }
+}
+
 // ============================================================================
 // Trait: C2paBindable - Content that can be bound to a manifest
 // ============================================================================
 
 /// Types that can be bound to a C2PA manifest.
 ///
-/// Implement this for your domain types to enable C2PA wrapping.
+/// Implement this for your domain types to enable C2PA wrapping, or derive
+/// it to hash every field.
 ///
 /// # Example
 ///
+/// Deriving the implementation:
+///
+/// ```
+/// use c2pa_primitives::C2paBindable;
+///
+/// #[derive(C2paBindable)]
+/// #[c2pa(media_type = "application/x-reading")]
+/// struct Reading {
+///     sensor: String,
+///     value: u32,
+///     #[c2pa(skip)]
+///     cached_label: String,
+/// }
+/// ```
+///
+/// Implementing it by hand:
+///
 /// ```ignore
 /// struct ImageData {
 ///     pixels: Vec<u8>,
@@ -242,8 +586,8 @@ impl IngredientRelation {
 /// }
 ///
 /// impl C2paBindable for ImageData {
-///     fn content_hash(&self) -> ContentHash {
-///         ContentHash::compute(&self.pixels)
+///     fn content_hash_with(&self, alg: HashAlg) -> ContentHash {
+///         ContentHash::compute_with(alg, &self.pixels)
 ///     }
 ///
 ///     fn media_type(&self) -> &str {
@@ -252,8 +596,27 @@ impl IngredientRelation {
 /// }
 /// ```
 pub trait C2paBindable {
-    /// Compute the content hash for asset binding.
-    fn content_hash(&self) -> ContentHash;
+    /// Compute the content hash for asset binding with `alg`.
+    fn content_hash_with(&self, alg: HashAlg) -> ContentHash;
+
+    /// SHA-256 content hash.
+    fn content_hash(&self) -> ContentHash {
+        self.content_hash_with(HashAlg::Sha256)
+    }
+
+    /// Content hash with the `length` bytes at `offset` left out, for
+    /// [`AssetBinding::Box`]. Only byte content supports this.
+    fn content_hash_excluding(
+        &self,
+        _alg: HashAlg,
+        _offset: u64,
+        _length: u64,
+    ) -> Result<ContentHash, TransformError> {
+        Err(TransformError::Binding(format!(
+            "{} content cannot exclude byte ranges",
+            self.media_type()
+        )))
+    }
 
     /// MIME type of the content.
     fn media_type(&self) -> &str {
@@ -263,20 +626,38 @@ pub trait C2paBindable {
 
 // Built-in implementations for common types
 impl C2paBindable for Vec<u8> {
-    fn content_hash(&self) -> ContentHash {
-        ContentHash::compute(self)
+    fn content_hash_with(&self, alg: HashAlg) -> ContentHash {
+        ContentHash::compute_with(alg, self)
+    }
+
+    fn content_hash_excluding(
+        &self,
+        alg: HashAlg,
+        offset: u64,
+        length: u64,
+    ) -> Result<ContentHash, TransformError> {
+        ContentHash::compute_excluding(alg, self, offset, length)
     }
 }
 
 impl C2paBindable for [u8] {
-    fn content_hash(&self) -> ContentHash {
-        ContentHash::compute(self)
+    fn content_hash_with(&self, alg: HashAlg) -> ContentHash {
+        ContentHash::compute_with(alg, self)
+    }
+
+    fn content_hash_excluding(
+        &self,
+        alg: HashAlg,
+        offset: u64,
+        length: u64,
+    ) -> Result<ContentHash, TransformError> {
+        ContentHash::compute_excluding(alg, self, offset, length)
     }
 }
 
 impl C2paBindable for String {
-    fn content_hash(&self) -> ContentHash {
-        ContentHash::compute(self.as_bytes())
+    fn content_hash_with(&self, alg: HashAlg) -> ContentHash {
+        ContentHash::compute_with(alg, self.as_bytes())
     }
 
     fn media_type(&self) -> &str {
@@ -285,8 +666,8 @@ impl C2paBindable for String {
 }
 
 impl C2paBindable for str {
-    fn content_hash(&self) -> ContentHash {
-        ContentHash::compute(self.as_bytes())
+    fn content_hash_with(&self, alg: HashAlg) -> ContentHash {
+        ContentHash::compute_with(alg, self.as_bytes())
     }
 
     fn media_type(&self) -> &str {
@@ -299,8 +680,8 @@ macro_rules! impl_bindable_for_primitive {
     ($($ty:ty),*) => {
         $(
             impl C2paBindable for $ty {
-                fn content_hash(&self) -> ContentHash {
-                    ContentHash::compute(self.to_le_bytes())
+                fn content_hash_with(&self, alg: HashAlg) -> ContentHash {
+                    ContentHash::compute_with(alg, self.to_le_bytes())
                 }
             }
         )*
@@ -339,19 +720,59 @@ pub trait C2paTransform<I: C2paBindable, O: C2paBindable> {
     ) -> Result<C2pa<O, Verified>, TransformError>;
 }
 
+/// Signer shared by every step of a pipeline.
+pub type SharedSigner = Arc<dyn Signer + Send + Sync>;
+
+/// Timestamp authority shared by every step of a pipeline.
+pub type SharedTimestampAuthority = Arc<dyn TimestampAuthority + Send + Sync>;
+
 /// Context for performing transformations.
-#[derive(Debug)]
+#[derive(Clone)]
 pub struct TransformContext {
     /// Generator label (e.g., "MyApp/1.0").
     pub generator: String,
-    /// Whether to require trusted timestamps.
+    /// Fail signing unless a trusted timestamp is obtained.
     pub require_timestamp: bool,
-    /// Custom assertions to add.
+    /// Assertions added to every manifest signed in this context.
     pub assertions: Vec<CustomAssertion>,
     /// Transform name (set by macro-generated code).
     pub transform_name: Option<String>,
     /// Parameter commits (name -> hash). Values are NOT stored.
     pub param_commits: Vec<(String, [u8; 32])>,
+    /// Openings for the salted commits, keyed by the manifest they went into.
+    /// Producer-side secrets: they never leave the process unless taken.
+    /// A finished pipeline hands its openings to the enclosing one (see
+    /// [`collect_param_openings`] for the top level).
+    pub param_openings: Vec<(ClaimHash, ParamOpening)>,
+    /// Names of the enclosing pipelines, outermost first.
+    pub pipeline: Vec<String>,
+    /// Signs every manifest produced in this context.
+    pub signer: SharedSigner,
+    /// Timestamps every signature produced in this context.
+    pub timestamp_authority: Option<SharedTimestampAuthority>,
+    /// Algorithm for the content and claim hashes of every manifest
+    /// produced in this context.
+    pub hash_alg: HashAlg,
+    /// Sign claims through COSE_Sign1 envelopes.
+    pub cose_sign1: bool,
+}
+
+impl std::fmt::Debug for TransformContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransformContext")
+            .field("generator", &self.generator)
+            .field("require_timestamp", &self.require_timestamp)
+            .field("assertions", &self.assertions)
+            .field("transform_name", &self.transform_name)
+            .field("param_commits", &self.param_commits)
+            .field("param_openings", &self.param_openings.len())
+            .field("pipeline", &self.pipeline)
+            .field("signer", &self.signer.algorithm().as_str())
+            .field("timestamp_authority", &self.timestamp_authority.is_some())
+            .field("hash_alg", &self.hash_alg)
+            .field("cose_sign1", &self.cose_sign1)
+            .finish()
+    }
 }
 
 impl TransformContext {
@@ -362,9 +783,65 @@ impl TransformContext {
             assertions: Vec::new(),
             transform_name: None,
             param_commits: Vec::new(),
+            param_openings: Vec::new(),
+            pipeline: Vec::new(),
+            signer: Arc::new(TestSigner),
+            timestamp_authority: None,
+            hash_alg: HashAlg::Sha256,
+            cose_sign1: false,
         }
     }
 
+    /// Sign with `signer` instead of the default [`TestSigner`].
+    pub fn with_signer(mut self, signer: impl Signer + Send + Sync + 'static) -> Self {
+        self.signer = Arc::new(signer);
+        self
+    }
+
+    /// Timestamp every signature with `authority`.
+    pub fn with_timestamp_authority(
+        mut self,
+        authority: impl TimestampAuthority + Send + Sync + 'static,
+    ) -> Self {
+        self.timestamp_authority = Some(Arc::new(authority));
+        self
+    }
+
+    /// Hash content and claims with `alg` instead of SHA-256.
+    pub fn with_hash_alg(mut self, alg: HashAlg) -> Self {
+        self.hash_alg = alg;
+        self
+    }
+
+    /// Sign claims through COSE_Sign1 envelopes (see [`CoseSign1`]).
+    pub fn with_cose_sign1(mut self, enabled: bool) -> Self {
+        self.cose_sign1 = enabled;
+        self
+    }
+
+    /// The signer for manifests produced in this context.
+    pub fn signer(&self) -> &(dyn Signer + Send + Sync) {
+        self.signer.as_ref()
+    }
+
+    /// Context for a sub-pipeline running inside this one.
+    ///
+    /// Inherits the generator (unless overridden), assertions, signer,
+    /// timestamp authority, timestamp policy, hash algorithm and signature
+    /// envelope; transform metadata and openings start empty.
+    pub fn sub_pipeline(&self, name: &str, generator: Option<&str>) -> Self {
+        let mut ctx = TransformContext::new(generator.unwrap_or(&self.generator))
+            .with_timestamp(self.require_timestamp)
+            .with_hash_alg(self.hash_alg)
+            .with_cose_sign1(self.cose_sign1);
+        ctx.assertions = self.assertions.clone();
+        ctx.signer = Arc::clone(&self.signer);
+        ctx.timestamp_authority = self.timestamp_authority.clone();
+        ctx.pipeline = self.pipeline.clone();
+        ctx.pipeline.push(name.to_string());
+        ctx
+    }
+
     pub fn with_timestamp(mut self, require: bool) -> Self {
         self.require_timestamp = require;
         self
@@ -388,6 +865,11 @@ impl TransformContext {
         self.param_commits.push((name, commit));
     }
 
+    /// Remove and return the openings recorded so far.
+    pub fn take_param_openings(&mut self) -> Vec<(ClaimHash, ParamOpening)> {
+        std::mem::take(&mut self.param_openings)
+    }
+
     /// Clear transform metadata for reuse.
     pub fn clear_transform_metadata(&mut self) {
         self.transform_name = None;
@@ -430,6 +912,12 @@ pub enum TransformError {
 
     #[error("C2PA error: {0}")]
     C2pa(String),
+
+    #[error("manifest error: {0}")]
+    Manifest(String),
+
+    #[error("validation failed: {0}")]
+    Validation(ValidationReport),
 }
 
 // ============================================================================
@@ -439,30 +927,199 @@ pub enum TransformError {
 /// Verify an unverified C2PA value.
 ///
 /// This is one of the only ways to obtain a `C2pa<T, Verified>`.
+/// Nothing in the provenance is taken on trust: the claim is rebuilt from
+/// its contents, the stored signature is checked against `trusted`, and the
+/// payload is checked against the asset binding.
 pub fn verify<T: C2paBindable>(
     value: C2pa<T, Unverified>,
-    expected_hash: &ClaimHash,
+    trusted: &TrustedKeys,
 ) -> Result<C2pa<T, Verified>, TransformError> {
-    // Verify the claim hash matches
-    if &value.provenance.claim_hash != expected_hash {
-        return Err(TransformError::Verification(
-            "claim hash mismatch".into(),
+    let mut statuses = check_claim(&value.provenance, trusted);
+
+    // Verify asset binding, rehashing the way it records
+    let (rehashed, expected) = match &value.provenance.asset_binding {
+        AssetBinding::Hash(hash) => (Ok(value.payload.content_hash_with(hash.alg())), hash),
+        AssetBinding::Box { offset, length, hash } => (
+            value.payload.content_hash_excluding(hash.alg(), *offset, *length),
+            hash,
+        ),
+    };
+    match rehashed {
+        Ok(rehashed) if rehashed == *expected => {}
+        Ok(_) => statuses.push(ValidationStatus::new(
+            StatusCode::BindingMismatch,
+            "payload does not match the asset binding",
+        )),
+        Err(err) => {
+            statuses.push(ValidationStatus::new(StatusCode::BindingMismatch, err.to_string()))
+        }
+    }
+
+    if !statuses.is_empty() {
+        return Err(TransformError::Validation(ValidationReport::for_manifest(
+            &value.provenance,
+            statuses,
+        )));
+    }
+
+    Ok(C2pa::new_verified(value.payload, value.provenance))
+}
+
+/// Check a claim on its own: recompute its hash and verify its signature.
+///
+/// Does not look at the payload; see [`verify`] for the full check.
+pub fn verify_claim(provenance: &Provenance, trusted: &TrustedKeys) -> Result<(), TransformError> {
+    let statuses = check_claim(provenance, trusted);
+    if statuses.is_empty() {
+        Ok(())
+    } else {
+        Err(TransformError::Validation(ValidationReport::for_manifest(
+            provenance, statuses,
+        )))
+    }
+}
+
+/// Claim hash and signature checks shared by [`verify`] and [`ChainVerifier`].
+pub(crate) fn check_claim(provenance: &Provenance, trusted: &TrustedKeys) -> Vec<ValidationStatus> {
+    let mut statuses = Vec::new();
+
+    // Recompute the claim hash from the claim contents
+    let claim_bytes = provenance.claim_bytes();
+    let recomputed = ClaimHash::compute(provenance.claim_hash.alg(), &claim_bytes);
+    if recomputed != provenance.claim_hash {
+        statuses.push(ValidationStatus::new(
+            StatusCode::ClaimHashMismatch,
+            "claim contents do not match the claim hash",
         ));
     }
 
-    // Verify asset binding
-    let computed = value.payload.content_hash();
-    match &value.provenance.asset_binding {
-        AssetBinding::Hash(expected) if expected == &computed => {}
-        AssetBinding::Box { hash, .. } if hash == &computed => {}
-        _ => {
-            return Err(TransformError::Verification(
-                "asset binding mismatch".into(),
-            ));
+    // Verify the claim signature against a trusted key or certificate chain
+    let Some(signature) = &provenance.signature else {
+        statuses.push(ValidationStatus::new(
+            StatusCode::SignatureMissing,
+            "manifest has no claim signature",
+        ));
+        return statuses;
+    };
+    let signed_bytes = match signature.signed_bytes(&claim_bytes) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            statuses.push(ValidationStatus::new(StatusCode::SignatureInvalid, err.to_string()));
+            return statuses;
+        }
+    };
+
+    // A valid timestamp fixes the time the certificates are checked at
+    let timestamp_status = match &signature.timestamp {
+        None if trusted.require_timestamp => Some(ValidationStatus::new(
+            StatusCode::TimestampMissing,
+            "claim signature has no timestamp",
+        )),
+        None => None,
+        Some(token) => check_timestamp(token, &signature.signature, trusted),
+    };
+    let signed_at = signature
+        .timestamp
+        .as_ref()
+        .filter(|_| timestamp_status.is_none())
+        .map(|token| token.time);
+
+    match trusted.find(&signature.key_id) {
+        None if !signature.certificate_chain.is_empty() => statuses.extend(check_certificate_chain(
+            signature,
+            &signed_bytes,
+            trusted,
+            signed_at,
+        )),
+        None => statuses.push(ValidationStatus::new(
+            StatusCode::UntrustedSigner,
+            format!("key {} is not trusted", hex::encode(&signature.key_id)),
+        )),
+        Some(verifier) if verifier.algorithm() != signature.algorithm => {
+            statuses.push(ValidationStatus::new(
+                StatusCode::SignatureInvalid,
+                format!(
+                    "algorithm mismatch: expected {}, found {}",
+                    verifier.algorithm().as_str(),
+                    signature.algorithm.as_str()
+                ),
+            ))
+        }
+        Some(verifier) => {
+            if let Err(err) = verifier.verify(&signed_bytes, &signature.signature) {
+                statuses.push(ValidationStatus::new(StatusCode::SignatureInvalid, err.to_string()));
+            }
         }
     }
 
-    Ok(C2pa::new_verified(value.payload, value.provenance))
+    statuses.extend(timestamp_status);
+    statuses
+}
+
+/// Verify a signature with the key in its leaf certificate, then check
+/// that the chain leads to a trust anchor.
+fn check_certificate_chain(
+    signature: &ClaimSignature,
+    signed_bytes: &[u8],
+    trusted: &TrustedKeys,
+    signed_at: Option<u64>,
+) -> Option<ValidationStatus> {
+    let leaf = match Certificate::from_bytes(&signature.certificate_chain[0]) {
+        Ok(leaf) => leaf,
+        Err(err) => {
+            return Some(ValidationStatus::new(StatusCode::CertificateInvalid, err.to_string()))
+        }
+    };
+    if leaf.key_id() != signature.key_id || leaf.algorithm != signature.algorithm {
+        return Some(ValidationStatus::new(
+            StatusCode::CertificateInvalid,
+            format!("leaf certificate `{}` does not hold the signing key", leaf.subject),
+        ));
+    }
+    let verified = signers::verifier_for(leaf.algorithm, &leaf.public_key)
+        .and_then(|verifier| verifier.verify(signed_bytes, &signature.signature));
+    if let Err(err) = verified {
+        return Some(ValidationStatus::new(StatusCode::SignatureInvalid, err.to_string()));
+    }
+
+    let time = trusted.validation_time.or(signed_at).unwrap_or_else(unix_time);
+    trusted
+        .trust_store
+        .validate(&signature.certificate_chain, time)
+        .err()
+}
+
+fn check_timestamp(
+    token: &TimestampToken,
+    signature: &[u8],
+    trusted: &TrustedKeys,
+) -> Option<ValidationStatus> {
+    if !token.covers(signature) {
+        return Some(ValidationStatus::new(
+            StatusCode::TimestampMismatch,
+            "timestamp was issued for a different signature",
+        ));
+    }
+    let Some(authority) = trusted.find_timestamp_authority(&token.tsa_key_id) else {
+        return Some(ValidationStatus::new(
+            StatusCode::TimestampUntrusted,
+            format!("timestamp authority {} is not trusted", hex::encode(&token.tsa_key_id)),
+        ));
+    };
+    if authority.algorithm() != token.algorithm {
+        return Some(ValidationStatus::new(
+            StatusCode::TimestampMismatch,
+            format!(
+                "algorithm mismatch: expected {}, found {}",
+                authority.algorithm().as_str(),
+                token.algorithm.as_str()
+            ),
+        ));
+    }
+    authority
+        .verify(&token.signed_bytes(), &token.signature)
+        .err()
+        .map(|err| ValidationStatus::new(StatusCode::TimestampMismatch, err.to_string()))
 }
 
 // ============================================================================
@@ -478,6 +1135,11 @@ pub struct C2paBuilder<T: C2paBindable> {
     ingredients: Vec<IngredientRef>,
     generator: String,
     assertions: Vec<CustomAssertion>,
+    require_timestamp: bool,
+    timestamp_authority: Option<SharedTimestampAuthority>,
+    hash_alg: HashAlg,
+    cose_sign1: bool,
+    exclusion: Option<(u64, u64)>,
 }
 
 impl<T: C2paBindable> C2paBuilder<T> {
@@ -488,9 +1150,27 @@ impl<T: C2paBindable> C2paBuilder<T> {
             ingredients: Vec::new(),
             generator: "c2pa_primitives/0.1".into(),
             assertions: Vec::new(),
+            require_timestamp: false,
+            timestamp_authority: None,
+            hash_alg: HashAlg::Sha256,
+            cose_sign1: false,
+            exclusion: None,
         }
     }
 
+    /// Apply a context's policy: its generator, its assertions (added
+    /// ahead of any added later), its timestamp authority, its timestamp
+    /// requirement, its hash algorithm and its signature envelope.
+    pub fn context(mut self, ctx: &TransformContext) -> Self {
+        self.generator = ctx.generator.clone();
+        self.assertions.extend(ctx.assertions.iter().cloned());
+        self.require_timestamp = ctx.require_timestamp;
+        self.timestamp_authority = ctx.timestamp_authority.clone();
+        self.hash_alg = ctx.hash_alg;
+        self.cose_sign1 = ctx.cose_sign1;
+        self
+    }
+
     /// Set the generator label.
     pub fn generator(mut self, generator: impl Into<String>) -> Self {
         self.generator = generator.into();
@@ -503,11 +1183,7 @@ impl<T: C2paBindable> C2paBuilder<T> {
         ingredient: &C2pa<I, Verified>,
         relation: IngredientRelation,
     ) -> Self {
-        self.ingredients.push(IngredientRef {
-            claim_hash: ingredient.provenance.claim_hash.clone(),
-            asset_binding: ingredient.provenance.asset_binding.clone(),
-            relationship: relation,
-        });
+        self.ingredients.push(IngredientRef::from_verified(ingredient, relation));
         self
     }
 
@@ -517,22 +1193,112 @@ impl<T: C2paBindable> C2paBuilder<T> {
         self
     }
 
+    /// Fail signing unless a trusted timestamp is obtained.
+    pub fn require_timestamp(mut self, require: bool) -> Self {
+        self.require_timestamp = require;
+        self
+    }
+
+    /// Timestamp the claim signature with `authority`.
+    pub fn timestamp_authority(
+        mut self,
+        authority: impl TimestampAuthority + Send + Sync + 'static,
+    ) -> Self {
+        self.timestamp_authority = Some(Arc::new(authority));
+        self
+    }
+
+    /// Hash the content and the claim with `alg` (SHA-256 by default).
+    pub fn hash_alg(mut self, alg: HashAlg) -> Self {
+        self.hash_alg = alg;
+        self
+    }
+
+    /// Bind the payload bytes with the `length` bytes at `offset` left out
+    /// ([`AssetBinding::Box`]), reserving that region for the manifest.
+    pub fn exclude(mut self, offset: u64, length: u64) -> Self {
+        self.exclusion = Some((offset, length));
+        self
+    }
+
+    /// Sign the claim through a COSE_Sign1 envelope instead of over the
+    /// bare claim bytes.
+    pub fn cose_sign1(mut self, enabled: bool) -> Self {
+        self.cose_sign1 = enabled;
+        self
+    }
+
     /// Sign and create a verified C2PA value.
     ///
-    /// In a real implementation, this would use the c2pa crate's signing.
-    /// For this prototype, we simulate the process.
-    pub fn sign(self, _signer: &dyn Signer) -> Result<C2pa<T, Verified>, TransformError> {
-        // Compute content hash
-        let content_hash = self.payload.content_hash();
-        let binding = AssetBinding::Hash(content_hash);
+    /// The signer is handed the same claim bytes that the claim hash
+    /// commits to, so a signature check covers everything in the claim.
+    /// With [`cose_sign1`](Self::cose_sign1) they are wrapped in a COSE
+    /// `Sig_structure` first.
+    ///
+    /// With a timestamp authority, the signature is timestamped; if that
+    /// fails the value is signed without a timestamp, unless one is required.
+    pub fn sign(self, signer: &dyn Signer) -> Result<C2pa<T, Verified>, TransformError> {
+        if self.require_timestamp && self.timestamp_authority.is_none() {
+            return Err(TransformError::Signing(
+                "a trusted timestamp is required but no timestamp authority is available".into(),
+            ));
+        }
+
+        // Compute content hash, leaving out the reserved region if any
+        let binding = match self.exclusion {
+            Some((offset, length)) => AssetBinding::Box {
+                offset,
+                length,
+                hash: self
+                    .payload
+                    .content_hash_excluding(self.hash_alg, offset, length)?,
+            },
+            None => AssetBinding::Hash(self.payload.content_hash_with(self.hash_alg)),
+        };
 
-        // Simulate claim hash computation (includes assertions)
-        let claim_hash = self.compute_claim_hash(&binding);
+        // Claim hash covers generator, binding, ingredients and assertions
+        let claim_bytes = encode_claim(
+            &self.generator,
+            &binding,
+            &self.ingredients,
+            &self.assertions,
+        );
+        let claim_hash = ClaimHash::compute(self.hash_alg, &claim_bytes);
+
+        let protected_header = if self.cose_sign1 {
+            Some(cose::protected_header(
+                signer.algorithm(),
+                &signer.key_id(),
+                signer.certificate_chain(),
+            )?)
+        } else {
+            None
+        };
+        let signature_bytes = match &protected_header {
+            Some(protected) => signer.sign(&cose::sig_structure(protected, &claim_bytes))?,
+            None => signer.sign(&claim_bytes)?,
+        };
+        let timestamp = match &self.timestamp_authority {
+            Some(authority) => match authority.timestamp(&signature_bytes) {
+                Ok(token) => Some(token),
+                Err(err) if self.require_timestamp => return Err(err),
+                Err(_) => None,
+            },
+            None => None,
+        };
+        let signature = ClaimSignature {
+            algorithm: signer.algorithm(),
+            signature: signature_bytes,
+            key_id: signer.key_id(),
+            certificate_chain: signer.certificate_chain().to_vec(),
+            timestamp,
+            protected_header,
+        };
 
         // Generate manifest ID
         let manifest_id = format!(
             "urn:uuid:{}",
-            uuid_from_bytes(&claim_hash.0[..16])
+            uuid_from_bytes(&claim_hash.as_bytes()[..16])
         );
 
         let provenance = if self.ingredients.is_empty() {
@@ -540,55 +1306,150 @@ impl<T: C2paBindable> C2paBuilder<T> {
         } else {
             Provenance::derived(manifest_id, claim_hash, binding, self.ingredients)
         };
+        let provenance = provenance
+            .with_generator(self.generator)
+            .with_assertions(self.assertions)
+            .with_signature(signature);
 
         Ok(C2pa::new_verified(self.payload, provenance))
     }
 
-    fn compute_claim_hash(&self, binding: &AssetBinding) -> ClaimHash {
-        let mut hasher = Sha256::new();
-        hasher.update(self.generator.as_bytes());
-
-        if let AssetBinding::Hash(h) = binding {
-            hasher.update(&h.0);
-        }
-
-        for ingredient in &self.ingredients {
-            hasher.update(&ingredient.claim_hash.0);
-        }
-
-        // Include assertions in claim hash
-        for assertion in &self.assertions {
-            hasher.update(assertion.label.as_bytes());
-            hasher.update(&assertion.data);
-        }
-
-        ClaimHash(hasher.finalize().into())
-    }
 }
 
 /// Minimal signer trait.
 pub trait Signer {
     fn sign(&self, data: &[u8]) -> Result<Vec<u8>, TransformError>;
     fn certificate_chain(&self) -> &[Vec<u8>];
+    /// Algorithm of the signatures produced by [`Signer::sign`].
+    ///
+    /// Defaults to [`SigningAlg::Placeholder`], which never verifies.
+    fn algorithm(&self) -> SigningAlg {
+        SigningAlg::Placeholder
+    }
+
+    /// Stable identifier of the signing key, recorded in the manifest.
+    ///
+    /// Defaults to the key id of the leaf certificate, or empty without a
+    /// parsable leaf certificate.
+    fn key_id(&self) -> Vec<u8> {
+        self.certificate_chain()
+            .first()
+            .and_then(|leaf| Certificate::from_bytes(leaf).ok())
+            .map_or_else(Vec::new, |leaf| leaf.key_id())
+    }
 }
 
-/// Placeholder signer for prototyping.
-pub struct TestSigner;
+/// Counterpart to [`Signer`]: checks a signature against a known public key.
+pub trait SignatureVerifier {
+    /// Returns `Ok(())` only if `signature` was produced over `data`
+    /// by the key this verifier holds.
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<(), TransformError>;
+    /// Algorithm this verifier accepts.
+    fn algorithm(&self) -> SigningAlg;
+    /// Identifier of the public key, matching [`Signer::key_id`].
+    fn key_id(&self) -> Vec<u8>;
+}
 
-impl Signer for TestSigner {
-    fn sign(&self, _data: &[u8]) -> Result<Vec<u8>, TransformError> {
-        // Placeholder - would use real signing in production
-        Ok(vec![0u8; 64])
+/// Set of public keys whose signatures [`verify`] accepts.
+#[derive(Default)]
+pub struct TrustedKeys {
+    keys: Vec<Box<dyn SignatureVerifier + Send + Sync>>,
+    timestamp_authorities: Vec<Box<dyn SignatureVerifier + Send + Sync>>,
+    require_timestamp: bool,
+    trust_store: TrustStore,
+    validation_time: Option<u64>,
+}
+
+impl TrustedKeys {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    fn certificate_chain(&self) -> &[Vec<u8>] {
-        &[]
+    /// Trust signatures made by the key behind `verifier`.
+    pub fn add_key(mut self, verifier: impl SignatureVerifier + Send + Sync + 'static) -> Self {
+        self.keys.push(Box::new(verifier));
+        self
     }
-}
+
+    /// Look up a trusted key by its identifier.
+    pub fn find(&self, key_id: &[u8]) -> Option<&(dyn SignatureVerifier + Send + Sync)> {
+        self.keys
+            .iter()
+            .find(|k| k.key_id() == key_id)
+            .map(|k| k.as_ref())
+    }
+
+    /// Trust timestamps issued by the authority behind `verifier`.
+    pub fn add_timestamp_authority(
+        mut self,
+        verifier: impl SignatureVerifier + Send + Sync + 'static,
+    ) -> Self {
+        self.timestamp_authorities.push(Box::new(verifier));
+        self
+    }
+
+    /// Look up a trusted timestamp authority by its key identifier.
+    pub fn find_timestamp_authority(
+        &self,
+        key_id: &[u8],
+    ) -> Option<&(dyn SignatureVerifier + Send + Sync)> {
+        self.timestamp_authorities
+            .iter()
+            .find(|k| k.key_id() == key_id)
+            .map(|k| k.as_ref())
+    }
+
+    /// Reject manifests whose signature carries no timestamp.
+    pub fn require_timestamp(mut self, require: bool) -> Self {
+        self.require_timestamp = require;
+        self
+    }
+
+    /// Trust signatures whose certificate chain leads to an anchor in `store`.
+    pub fn with_trust_store(mut self, store: TrustStore) -> Self {
+        self.trust_store = store;
+        self
+    }
+
+    /// Check certificate validity at `time` (seconds since the Unix epoch).
+    ///
+    /// By default certificates are checked at the trusted timestamp of the
+    /// signature, or at the current time if it has none.
+    pub fn at_time(mut self, time: u64) -> Self {
+        self.validation_time = Some(time);
+        self
+    }
+}
+
+/// Placeholder signer for prototyping.
+pub struct TestSigner;
+
+impl Signer for TestSigner {
+    fn sign(&self, _data: &[u8]) -> Result<Vec<u8>, TransformError> {
+        // Placeholder - would use real signing in production
+        Ok(vec![0u8; 64])
+    }
+
+    fn certificate_chain(&self) -> &[Vec<u8>] {
+        &[]
+    }
+}
+
+/// Key identifier derived from a public key: SHA-256 of its encoding.
+pub fn key_id_for(public_key: &[u8]) -> Vec<u8> {
+    Sha256::digest(public_key).to_vec()
+}
 
 // ============================================================================
-// Utility Functions
-// ============================================================================
+// Utility Functions
+// ============================================================================
+
+/// Current time in seconds since the Unix epoch.
+pub(crate) fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
 
 fn uuid_from_bytes(bytes: &[u8]) -> String {
     format!(
@@ -650,10 +1511,10 @@ where
 
         // Build with ingredient reference
         let builder = C2paBuilder::new(output)
-            .generator(&ctx.generator)
+            .context(ctx)
             .add_ingredient(input, IngredientRelation::ParentOf);
 
-        builder.sign(&TestSigner)
+        builder.sign(ctx.signer())
     }
 }
 
@@ -694,8 +1555,8 @@ impl Invoice {
 }
 
 impl C2paBindable for Invoice {
-    fn content_hash(&self) -> ContentHash {
-        ContentHash::compute(self.to_bytes())
+    fn content_hash_with(&self, alg: HashAlg) -> ContentHash {
+        ContentHash::compute_with(alg, self.to_bytes())
     }
 
     fn media_type(&self) -> &str {
@@ -747,12 +1608,12 @@ impl Image {
 }
 
 impl C2paBindable for Image {
-    fn content_hash(&self) -> ContentHash {
+    fn content_hash_with(&self, alg: HashAlg) -> ContentHash {
         let mut data = Vec::new();
         data.extend_from_slice(&self.width.to_le_bytes());
         data.extend_from_slice(&self.height.to_le_bytes());
         data.extend_from_slice(&self.pixels);
-        ContentHash::compute(data)
+        ContentHash::compute_with(alg, data)
     }
 
     fn media_type(&self) -> &str {
@@ -776,7 +1637,11 @@ impl C2paBindable for Image {
 ///
 /// let unverified_bytes = C2pa::<Vec<u8>, Unverified>::new(
 ///     b"1:100".to_vec(),
-///     Provenance::root("test", ClaimHash([0; 32]), AssetBinding::Hash(ContentHash([0; 32]))),
+///     Provenance::root(
+///         "test",
+///         ClaimHash::from_bytes([0; 32]),
+///         AssetBinding::Hash(ContentHash::from_bytes([0; 32])),
+///     ),
 /// );
 /// let parse = ParseTransform::<Invoice>::new();
 /// let mut ctx = TransformContext::new("test");
@@ -808,9 +1673,9 @@ impl C2paTransform<Vec<u8>, Invoice> for ParseTransform<Invoice> {
         let invoice = Invoice::from_bytes(input.payload())?;
 
         C2paBuilder::new(invoice)
-            .generator(&ctx.generator)
+            .context(ctx)
             .add_ingredient(input, IngredientRelation::DerivedFrom)
-            .sign(&TestSigner)
+            .sign(ctx.signer())
     }
 }
 
@@ -851,9 +1716,9 @@ impl C2paTransform<Image, Image> for RedactTransform {
         }
 
         C2paBuilder::new(output)
-            .generator(&ctx.generator)
+            .context(ctx)
             .add_ingredient(input, IngredientRelation::DerivedFrom)
-            .sign(&TestSigner)
+            .sign(ctx.signer())
     }
 }
 
@@ -873,53 +1738,38 @@ pub trait C2paComposite<A: C2paBindable, B: C2paBindable, O: C2paBindable> {
     ) -> Result<C2pa<O, Verified>, TransformError>;
 }
 
-/// Composite transform that concatenates two images horizontally.
-pub struct HConcatTransform;
-
-impl C2paComposite<Image, Image, Image> for HConcatTransform {
-    fn compose(
-        &self,
-        a: &C2pa<Image, Verified>,
-        b: &C2pa<Image, Verified>,
-        ctx: &mut TransformContext,
-    ) -> Result<C2pa<Image, Verified>, TransformError> {
-        let img_a = a.payload();
-        let img_b = b.payload();
-
-        // Heights must match for horizontal concat
-        if img_a.height != img_b.height {
-            return Err(TransformError::C2pa("height mismatch".into()));
-        }
-
-        let new_width = img_a.width + img_b.width;
-        let height = img_a.height;
-        let mut pixels = Vec::with_capacity((new_width * height) as usize);
-
-        for y in 0..height {
-            // Copy row from A
-            let a_start = (y * img_a.width) as usize;
-            let a_end = a_start + img_a.width as usize;
-            pixels.extend_from_slice(&img_a.pixels[a_start..a_end]);
-
-            // Copy row from B
-            let b_start = (y * img_b.width) as usize;
-            let b_end = b_start + img_b.width as usize;
-            pixels.extend_from_slice(&img_b.pixels[b_start..b_end]);
-        }
-
-        let output = Image {
-            width: new_width,
-            height,
-            pixels,
-        };
-
-        // Add BOTH sources as ingredients - this creates the DAG
-        C2paBuilder::new(output)
-            .generator(&ctx.generator)
-            .add_ingredient(a, IngredientRelation::ComposedFrom)
-            .add_ingredient(b, IngredientRelation::ComposedFrom)
-            .sign(&TestSigner)
-    }
+/// Concatenate two images horizontally.
+///
+/// [`HConcatTransform`] is the provenance-aware composite, recording both
+/// images as ingredients.
+#[c2pa_composite(name = "hconcat", composite = HConcatTransform)]
+pub fn hconcat(img_a: &Image, img_b: &Image) -> Result<Image, String> {
+    // Heights must match for horizontal concat
+    if img_a.height != img_b.height {
+        return Err("height mismatch".into());
+    }
+
+    let new_width = img_a.width + img_b.width;
+    let height = img_a.height;
+    let mut pixels = Vec::with_capacity((new_width * height) as usize);
+
+    for y in 0..height {
+        // Copy row from A
+        let a_start = (y * img_a.width) as usize;
+        let a_end = a_start + img_a.width as usize;
+        pixels.extend_from_slice(&img_a.pixels[a_start..a_end]);
+
+        // Copy row from B
+        let b_start = (y * img_b.width) as usize;
+        let b_end = b_start + img_b.width as usize;
+        pixels.extend_from_slice(&img_b.pixels[b_start..b_end]);
+    }
+
+    Ok(Image {
+        width: new_width,
+        height,
+        pixels,
+    })
 }
 
 /// Generic function-based composite transform.
@@ -965,10 +1815,10 @@ where
         let output = (self.func)(a.payload(), b.payload());
 
         C2paBuilder::new(output)
-            .generator(&ctx.generator)
+            .context(ctx)
             .add_ingredient(a, IngredientRelation::ComposedFrom)
             .add_ingredient(b, IngredientRelation::ComposedFrom)
-            .sign(&TestSigner)
+            .sign(ctx.signer())
     }
 }
 
@@ -1021,73 +1871,103 @@ pub mod transform_helper {
     /// * `input` - The verified input (becomes an ingredient)
     /// * `transform_name` - Name of the transform for provenance metadata
     /// * `relationship` - The ingredient relationship
-    /// * `param_commits` - Parameter commits (name, hash) pairs - values not stored
+    /// * `param_openings` - Salted parameter commitments; only the commitment
+    ///   is stored in the manifest, the openings are kept in `ctx`
     /// * `ctx` - Transform context
     pub fn build_transform_result<I, O>(
         output: O,
         input: &C2pa<I, Verified>,
         transform_name: &str,
         relationship: IngredientRelation,
-        param_commits: Vec<(String, [u8; 32])>,
+        param_openings: Vec<ParamOpening>,
         ctx: &mut TransformContext,
     ) -> Result<C2pa<O, Verified>, TransformError>
     where
         I: C2paBindable,
         O: C2paBindable,
+    {
+        build_multi_input_result(
+            output,
+            vec![IngredientRef::from_verified(input, relationship)],
+            transform_name,
+            param_openings,
+            None,
+            ctx,
+        )
+    }
+
+    /// Build a transform result that records any number of verified inputs.
+    ///
+    /// Every `#[c2pa_transform]` and `#[c2pa_composite]` wrapper calls
+    /// this, as does [`build_transform_result`]; each entry in
+    /// `ingredients` becomes an ingredient of the output, in order.
+    /// `signer` overrides the context's signer.
+    pub fn build_multi_input_result<O>(
+        output: O,
+        ingredients: Vec<IngredientRef>,
+        transform_name: &str,
+        param_openings: Vec<ParamOpening>,
+        signer: Option<&dyn Signer>,
+        ctx: &mut TransformContext,
+    ) -> Result<C2pa<O, Verified>, TransformError>
+    where
+        O: C2paBindable,
     {
         // Record transform metadata in context
         ctx.set_transform_name(transform_name);
+        let param_commits: Vec<(String, [u8; 32])> = param_openings
+            .iter()
+            .map(|opening| (opening.name.clone(), opening.commitment()))
+            .collect();
         for (param_name, commit_hash) in &param_commits {
             ctx.add_param_commit(param_name.clone(), *commit_hash);
         }
 
         // Build the result with provenance
-        let mut builder = C2paBuilder::new(output)
-            .generator(&ctx.generator)
-            .add_ingredient(input, relationship);
+        let mut builder = C2paBuilder::new(output).context(ctx);
+        builder.ingredients.extend(ingredients);
 
         // Add transform assertion if we have metadata
         if !transform_name.is_empty() || !param_commits.is_empty() {
-            let assertion = build_transform_assertion(transform_name, &param_commits);
+            let assertion = build_transform_assertion(transform_name, &param_commits, &ctx.pipeline);
             builder = builder.add_assertion(assertion);
         }
 
-        builder.sign(&TestSigner)
+        let result = builder.sign(signer.unwrap_or(ctx.signer()))?;
+
+        // Keep the openings so the producer can later reveal them
+        let claim_hash = &result.provenance().claim_hash;
+        ctx.param_openings.extend(
+            param_openings
+                .into_iter()
+                .map(|opening| (claim_hash.clone(), opening)),
+        );
+        Ok(result)
     }
 
     /// Build a custom assertion for transform metadata.
     fn build_transform_assertion(
         transform_name: &str,
         param_commits: &[(String, [u8; 32])],
+        pipeline: &[String],
     ) -> CustomAssertion {
-        // Build a simple JSON-like structure for the assertion
         // Note: We only store commits (hashes), NOT raw parameter values
-        let commits_json: String = param_commits
+        let commits: serde_json::Map<String, serde_json::Value> = param_commits
             .iter()
-            .map(|(name, hash)| {
-                format!(
-                    r#""{}":{:?}"#,
-                    name,
-                    hex::encode(hash)
-                )
-            })
-            .collect::<Vec<_>>()
-            .join(",");
-
-        let json = format!(
-            r#"{{"transform":"{}","param_commits":{{{}}}}}"#,
-            transform_name,
-            commits_json
-        );
+            .map(|(name, hash)| (name.clone(), hex::encode(hash).into()))
+            .collect();
 
-        CustomAssertion::json("c2pa.transform", &json)
-    }
-}
+        let mut json = serde_json::Map::new();
+        json.insert("transform".into(), transform_name.into());
+        json.insert("param_commits".into(), commits.into());
 
-/// Simple hex encoding helper
-mod hex {
-    pub fn encode(bytes: &[u8]) -> String {
-        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+        // Enclosing pipelines, so sub-pipeline steps can be told apart
+        if !pipeline.is_empty() {
+            json.insert("pipeline".into(), pipeline.into());
+        }
+        let json = serde_json::Value::Object(json).to_string();
+
+        CustomAssertion::json("c2pa.transform", &json)
     }
 }
 
@@ -1096,36 +1976,147 @@ mod hex {
 // ============================================================================
 
 use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll};
 
 thread_local! {
-    static CURRENT_CTX: RefCell<Option<TransformContext>> = const { RefCell::new(None) };
+    /// Active pipeline contexts, innermost last.
+    static CTX_STACK: RefCell<Vec<TransformContext>> = const { RefCell::new(Vec::new()) };
+
+    /// Sinks installed by [`collect_param_openings`], innermost last.
+    static OPENING_SINKS: RefCell<Vec<OpeningSink>> = const { RefCell::new(Vec::new()) };
 }
 
-/// Initialize a new pipeline context and run the closure within it.
+/// Openings collected outside any single context.
+pub(crate) type OpeningSink = Arc<Mutex<Vec<(ClaimHash, ParamOpening)>>>;
+
+/// Lock a sink, ignoring poisoning: a panicking producer leaves the
+/// openings it already pushed intact.
+pub(crate) fn lock_sink(sink: &OpeningSink) -> MutexGuard<'_, Vec<(ClaimHash, ParamOpening)>> {
+    sink.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn current_opening_sink() -> Option<OpeningSink> {
+    OPENING_SINKS.with(|cell| cell.borrow().last().cloned())
+}
+
+/// Pops a pushed context when dropped, so the stack is restored even if
+/// the pipeline body panics.
+struct CtxGuard {
+    depth: usize,
+}
+
+impl CtxGuard {
+    fn push(ctx: TransformContext) -> Self {
+        CTX_STACK.with(|cell| {
+            let mut stack = cell.borrow_mut();
+            stack.push(ctx);
+            Self { depth: stack.len() - 1 }
+        })
+    }
+
+    /// Pop the context normally and hand it back.
+    fn pop(self) -> TransformContext {
+        let ctx = CTX_STACK.with(|cell| {
+            let mut stack = cell.borrow_mut();
+            stack.truncate(self.depth + 1);
+            stack.pop().expect("pipeline context stack underflow")
+        });
+        std::mem::forget(self);
+        ctx
+    }
+
+    /// Pop the context at the end of its pipeline and hand its openings on:
+    /// to the enclosing context if there is one, otherwise to `sink` or the
+    /// sink of the surrounding [`collect_param_openings`].
+    fn finish(self, sink: Option<OpeningSink>) {
+        let openings = self.pop().take_param_openings();
+        if openings.is_empty() {
+            return;
+        }
+        CTX_STACK.with(|cell| match cell.borrow_mut().last_mut() {
+            Some(parent) => parent.param_openings.extend(openings),
+            None => {
+                if let Some(sink) = sink.or_else(current_opening_sink) {
+                    lock_sink(&sink).extend(openings);
+                }
+            }
+        });
+    }
+}
+
+impl Drop for CtxGuard {
+    fn drop(&mut self) {
+        // `try_with` because this may run during thread teardown
+        let _ = CTX_STACK.try_with(|cell| cell.borrow_mut().truncate(self.depth));
+    }
+}
+
+/// Context for a pipeline named `name`, nested in the current one if any.
+fn pipeline_ctx(
+    name: &str,
+    generator: Option<&str>,
+    signer: Option<SharedSigner>,
+    hash_alg: Option<HashAlg>,
+) -> TransformContext {
+    let mut ctx = CTX_STACK.with(|cell| match cell.borrow().last() {
+        Some(parent) => parent.sub_pipeline(name, generator),
+        None => {
+            let mut ctx = TransformContext::new(generator.unwrap_or("c2pa_pipeline"));
+            ctx.pipeline.push(name.to_string());
+            ctx
+        }
+    });
+    if let Some(signer) = signer {
+        ctx.signer = signer;
+    }
+    if let Some(alg) = hash_alg {
+        ctx.hash_alg = alg;
+    }
+    ctx
+}
+
+/// Run the closure within a fresh context, pushed on top of any active one.
 ///
-/// Used by `#[c2pa_pipeline]` macro.
+/// The context is popped when the closure returns or unwinds.
 #[doc(hidden)]
 pub fn with_new_ctx<F, R>(generator: &str, f: F) -> R
 where
     F: FnOnce() -> R,
 {
-    CURRENT_CTX.with(|cell| {
-        if cell.borrow().is_some() {
-            panic!("c2pa_pipeline cannot be nested");
-        }
-        *cell.borrow_mut() = Some(TransformContext::new(generator));
-    });
-
+    let guard = CtxGuard::push(TransformContext::new(generator));
     let result = f();
+    guard.finish(None);
+    result
+}
 
-    CURRENT_CTX.with(|cell| {
-        *cell.borrow_mut() = None;
-    });
-
+/// Run the closure as the pipeline `name`.
+///
+/// At the top level this starts a new context; inside another pipeline it
+/// starts a sub-pipeline that inherits the parent's settings and, unless
+/// overridden, its generator, signer and hash algorithm.
+///
+/// Used by `#[c2pa_pipeline]` macro.
+#[doc(hidden)]
+pub fn with_pipeline_ctx<F, R>(
+    name: &str,
+    generator: Option<&str>,
+    signer: Option<SharedSigner>,
+    hash_alg: Option<HashAlg>,
+    f: F,
+) -> R
+where
+    F: FnOnce() -> R,
+{
+    let guard = CtxGuard::push(pipeline_ctx(name, generator, signer, hash_alg));
+    let result = f();
+    guard.finish(None);
     result
 }
 
-/// Execute a closure with mutable access to the current context.
+/// Execute a closure with mutable access to the current (innermost) context.
 ///
 /// Panics if called outside a `#[c2pa_pipeline]`.
 #[doc(hidden)]
@@ -1133,18 +2124,138 @@ pub fn with_ctx<F, R>(f: F) -> R
 where
     F: FnOnce(&mut TransformContext) -> R,
 {
-    CURRENT_CTX.with(|cell| {
-        let mut borrow = cell.borrow_mut();
-        let ctx = borrow
-            .as_mut()
+    CTX_STACK.with(|cell| {
+        let mut stack = cell.borrow_mut();
+        let ctx = stack
+            .last_mut()
             .expect("with_ctx called outside #[c2pa_pipeline]");
         f(ctx)
     })
 }
 
+/// Copy of the innermost active context, if any.
+pub(crate) fn current_ctx() -> Option<TransformContext> {
+    CTX_STACK.with(|cell| cell.borrow().last().cloned())
+}
+
+/// Run the closure with `ctx` pushed as the innermost context, handing
+/// back the context as it was left.
+pub(crate) fn with_pushed_ctx<F, R>(ctx: TransformContext, f: F) -> (R, TransformContext)
+where
+    F: FnOnce() -> R,
+{
+    let guard = CtxGuard::push(ctx);
+    let result = f();
+    (result, guard.pop())
+}
+
 /// Check if a pipeline context is currently active.
 pub fn has_ctx() -> bool {
-    CURRENT_CTX.with(|cell| cell.borrow().is_some())
+    CTX_STACK.with(|cell| !cell.borrow().is_empty())
+}
+
+/// Number of active pipeline contexts on this thread.
+pub fn ctx_depth() -> usize {
+    CTX_STACK.with(|cell| cell.borrow().len())
+}
+
+/// Run the closure and return, with its result, the parameter openings of
+/// every top-level pipeline that finished inside it.
+///
+/// Openings recorded in a sub-pipeline are handed to the enclosing pipeline
+/// when it returns; this is where they end up once the outermost one does.
+/// Async pipelines created inside the closure deliver here too, even when
+/// polled on another thread, as long as they finish before it returns.
+pub fn collect_param_openings<F, R>(f: F) -> (R, Vec<(ClaimHash, ParamOpening)>)
+where
+    F: FnOnce() -> R,
+{
+    /// Uninstalls the sink even if the closure panics.
+    struct SinkGuard;
+
+    impl Drop for SinkGuard {
+        fn drop(&mut self) {
+            let _ = OPENING_SINKS.try_with(|cell| cell.borrow_mut().pop());
+        }
+    }
+
+    let sink = OpeningSink::default();
+    OPENING_SINKS.with(|cell| cell.borrow_mut().push(Arc::clone(&sink)));
+    let guard = SinkGuard;
+    let result = f();
+    drop(guard);
+    let openings = std::mem::take(&mut *lock_sink(&sink));
+    (result, openings)
+}
+
+/// Run a future as the pipeline `name`.
+///
+/// The context is owned by the returned future rather than by the thread:
+/// it is pushed onto the thread's context stack only while the future is
+/// being polled, so it follows the task across executor threads and never
+/// leaks into other tasks sharing a thread. Nesting follows
+/// [`with_pipeline_ctx`], relative to the pipeline that first polls it.
+///
+/// Used by `#[c2pa_pipeline]` on `async fn`.
+#[doc(hidden)]
+pub fn with_pipeline_ctx_async<F: Future>(
+    name: &str,
+    generator: Option<&str>,
+    signer: Option<SharedSigner>,
+    hash_alg: Option<HashAlg>,
+    future: F,
+) -> CtxFuture<F> {
+    CtxFuture {
+        future: Box::pin(future),
+        ctx: None,
+        name: name.to_string(),
+        generator: generator.map(str::to_string),
+        signer,
+        hash_alg,
+        sink: current_opening_sink(),
+    }
+}
+
+/// Future returned by [`with_pipeline_ctx_async`].
+pub struct CtxFuture<F: Future> {
+    future: Pin<Box<F>>,
+    /// Created on first poll, when the parent pipeline (if any) is active.
+    ctx: Option<TransformContext>,
+    name: String,
+    generator: Option<String>,
+    signer: Option<SharedSigner>,
+    hash_alg: Option<HashAlg>,
+    /// Where the openings go if this turns out to be a top-level pipeline.
+    sink: Option<OpeningSink>,
+}
+
+impl<F: Future> Future for CtxFuture<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let this = &mut *self;
+        let ctx = match this.ctx.take() {
+            Some(ctx) => ctx,
+            None => pipeline_ctx(
+                &this.name,
+                this.generator.as_deref(),
+                this.signer.take(),
+                this.hash_alg,
+            ),
+        };
+
+        // Push our context for the duration of the poll; the guard pops it
+        // even if the inner future panics.
+        let guard = CtxGuard::push(ctx);
+        let poll = this.future.as_mut().poll(cx);
+
+        if poll.is_pending() {
+            this.ctx = Some(guard.pop());
+        } else {
+            guard.finish(this.sink.take());
+        }
+        poll
+    }
 }
 
 // ============================================================================
@@ -1156,7 +2267,7 @@ pub mod debug {
     use super::*;
 
     /// Format hash as short hex string (first 8 bytes).
-    pub fn hash_short(hash: &[u8; 32]) -> String {
+    pub fn hash_short(hash: &[u8]) -> String {
         hash.iter().take(8).map(|b| format!("{:02x}", b)).collect()
     }
 
@@ -1172,12 +2283,15 @@ pub mod debug {
         println!("│ payload      : {:?}", value.payload());
         println!("│ manifest_id  : {}", prov.manifest_id);
         println!("│ claim_hash   : {}...", hash_short(prov.claim_hash.as_bytes()));
-        println!("│ content_hash : {}...", hash_short(&content_hash.0));
+        println!("│ content_hash : {}...", hash_short(content_hash.as_bytes()));
         println!("│ ingredients  : {}", prov.ingredients.len());
         println!("└────────────────────────────────────");
     }
 
     /// Verify that ingredient's claim_hash matches parent's claim_hash.
+    ///
+    /// Only compares the first ingredient against one parent and prints
+    /// the result; use [`ChainVerifier`] to check a whole provenance graph.
     pub fn verify_chain<T, U>(child: &C2pa<T, Verified>, parent: &C2pa<U, Verified>, step_name: &str)
     where
         T: C2paBindable,
@@ -1214,6 +2328,13 @@ pub mod debug {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
+
+    /// Ed25519 signer with a fixed key derived from `seed`, for tests that
+    /// need signatures a `TrustedKeys` can verify.
+    pub(crate) fn test_signer(seed: u8) -> Ed25519Signer {
+        Ed25519Signer::from_raw(&[seed; 32]).unwrap()
+    }
 
     #[test]
     fn test_verified_type_safety() {
@@ -1271,14 +2392,76 @@ mod tests {
         assert_eq!(v2.provenance().ingredients[0].claim_hash, v1.provenance().claim_hash);
     }
 
+    #[test]
+    fn test_hconcat_composes_both_images() {
+        let a = C2paBuilder::new(Image::new(2, 2, 1)).sign(&TestSigner).unwrap();
+        let b = C2paBuilder::new(Image::new(1, 2, 9)).sign(&TestSigner).unwrap();
+        let mut ctx = TransformContext::new("test");
+
+        let joined = HConcatTransform.compose(&a, &b, &mut ctx).unwrap();
+        assert_eq!(joined.payload().pixels, [1, 1, 9, 1, 1, 9]);
+        let ingredients = &joined.provenance().ingredients;
+        assert_eq!(ingredients[0].claim_hash, a.provenance().claim_hash);
+        assert_eq!(ingredients[1].claim_hash, b.provenance().claim_hash);
+        assert!(ingredients.iter().all(|i| i.relationship == IngredientRelation::ComposedFrom));
+
+        let short = C2paBuilder::new(Image::new(1, 1, 0)).sign(&TestSigner).unwrap();
+        assert!(HConcatTransform.compose(&a, &short, &mut ctx).is_err());
+    }
+
+    #[test]
+    fn test_context_assertions_attached_to_output() {
+        let input = C2paBuilder::new(1u32).sign(&TestSigner).unwrap();
+        let mut ctx = TransformContext::new("test")
+            .add_assertion(CustomAssertion::json("org.example.policy", r#"{"tier":1}"#));
+
+        let output = FnTransform::new(|x: &u32| x + 1, "increment")
+            .transform(&input, &mut ctx)
+            .unwrap();
+        let labels: Vec<_> = output.provenance().assertions.iter().map(|a| &a.label).collect();
+        assert_eq!(labels, ["org.example.policy"]);
+    }
+
+    #[test]
+    fn test_transform_assertion_escapes_names() {
+        let input = C2paBuilder::new(1u32).sign(&TestSigner).unwrap();
+        let pipeline = "say \"hi\"\u{1b}[0m";
+        let mut ctx = TransformContext::new("test").sub_pipeline(pipeline, None);
+
+        let output = transform_helper::build_transform_result(
+            2u32,
+            &input,
+            "step\\\"\n",
+            IngredientRelation::DerivedFrom,
+            Vec::new(),
+            &mut ctx,
+        )
+        .unwrap();
+        let assertion = &output.provenance().assertions[0];
+        assert!(!assertion.data.contains(&0x1b));
+
+        let json: serde_json::Value = serde_json::from_slice(&assertion.data).unwrap();
+        assert_eq!(json["transform"], "step\\\"\n");
+        assert_eq!(json["pipeline"], serde_json::json!([pipeline]));
+    }
+
+    #[test]
+    fn test_required_timestamp_fails_signing() {
+        let input = C2paBuilder::new(1u32).sign(&TestSigner).unwrap();
+        let mut ctx = TransformContext::new("test").with_timestamp(true);
+
+        let result = FnTransform::new(|x: &u32| x + 1, "increment").transform(&input, &mut ctx);
+        assert!(matches!(result, Err(TransformError::Signing(_))));
+    }
+
     #[test]
     fn test_unverified_cannot_become_verified_directly() {
         let unverified = C2pa::<u32, Unverified>::new(
             42,
             Provenance::root(
                 "test",
-                ClaimHash([0; 32]),
-                AssetBinding::Hash(ContentHash([0; 32])),
+                ClaimHash::from_bytes([0; 32]),
+                AssetBinding::Hash(ContentHash::from_bytes([0; 32])),
             ),
         );
 
@@ -1288,11 +2471,453 @@ mod tests {
         // let _: C2pa<u32, Verified> = unverified;
 
         // Must go through verification
-        let result = verify(unverified, &ClaimHash([0; 32]));
-        // Will fail because content hash doesn't match
+        let result = verify(unverified, &TrustedKeys::new());
+        // Will fail because the claim does not match its hash
         assert!(result.is_err());
     }
 
+    fn signed_unverified(signer: &Ed25519Signer) -> C2pa<u32, Unverified> {
+        let parent = C2paBuilder::new(1u32).sign(signer).unwrap();
+        let child = C2paBuilder::new(2u32)
+            .generator("test")
+            .add_ingredient(&parent, IngredientRelation::ParentOf)
+            .add_assertion(CustomAssertion::json("c2pa.test", "{}"))
+            .sign(signer)
+            .unwrap();
+        C2pa::new(*child.payload(), child.provenance().clone())
+    }
+
+    #[test]
+    fn test_verify_accepts_trusted_signature() {
+        let signer = test_signer(4);
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+
+        let verified = verify(signed_unverified(&signer), &trusted).unwrap();
+        assert_eq!(*verified.payload(), 2);
+    }
+
+    #[test]
+    fn test_verify_rejects_untrusted_signer() {
+        let signer = test_signer(4);
+        let other = test_signer(5);
+        let trusted = TrustedKeys::new().add_key(other.verifier());
+
+        let err = verify(signed_unverified(&signer), &trusted).unwrap_err();
+        let TransformError::Validation(report) = err else {
+            panic!("expected a validation report");
+        };
+        assert!(report.has(StatusCode::UntrustedSigner));
+        assert_eq!(report.failures().count(), 1);
+    }
+
+    #[test]
+    fn test_verify_rejects_forged_claim() {
+        let signer = test_signer(4);
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+
+        // Swapping the ingredient no longer matches the stored claim hash
+        let mut provenance = signed_unverified(&signer).provenance().clone();
+        provenance.ingredients[0].claim_hash = ClaimHash::from_bytes([9; 32]);
+        let forged = C2pa::new(2u32, provenance.clone());
+        assert!(matches!(
+            verify(forged, &trusted),
+            Err(TransformError::Validation(r)) if r.has(StatusCode::ClaimHashMismatch)
+        ));
+
+        // Re-hashing the forged claim still fails the signature check
+        provenance.claim_hash = ClaimHash::compute(HashAlg::Sha256, &provenance.claim_bytes());
+        let forged = C2pa::new(2u32, provenance);
+        assert!(matches!(
+            verify(forged, &trusted),
+            Err(TransformError::Validation(r))
+                if r.has(StatusCode::SignatureInvalid) && !r.has(StatusCode::ClaimHashMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_payload() {
+        let signer = test_signer(4);
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+
+        let provenance = signed_unverified(&signer).provenance().clone();
+        assert!(matches!(
+            verify(C2pa::new(3u32, provenance), &trusted),
+            Err(TransformError::Validation(r)) if r.has(StatusCode::BindingMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_verify_uses_recorded_hash_alg() {
+        let signer = test_signer(4);
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+        let parent = C2paBuilder::new(1u32).sign(&signer).unwrap();
+
+        for alg in [HashAlg::Sha384, HashAlg::Sha512, HashAlg::Blake3] {
+            let ctx = TransformContext::new("test").with_hash_alg(alg);
+            let child = C2paBuilder::new(2u32)
+                .context(&ctx)
+                .add_ingredient(&parent, IngredientRelation::ParentOf)
+                .sign(&signer)
+                .unwrap();
+            let provenance = child.provenance().clone();
+            assert_eq!(provenance.claim_hash.alg(), alg);
+            assert_eq!(provenance.claim_hash.as_bytes().len(), alg.output_len());
+            assert_eq!(provenance.asset_binding, AssetBinding::Hash(2u32.content_hash_with(alg)));
+            // The ingredient keeps the parent's own algorithm
+            assert_eq!(provenance.ingredients[0].claim_hash.alg(), HashAlg::Sha256);
+
+            assert!(verify(C2pa::new(2u32, provenance.clone()), &trusted).is_ok());
+            assert!(matches!(
+                verify(C2pa::new(3u32, provenance), &trusted),
+                Err(TransformError::Validation(r)) if r.has(StatusCode::BindingMismatch)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_verify_dispatches_on_algorithm() {
+        let testdata = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+        let p256 = P256Signer::from_pkcs8_file(testdata.join("p256.pem")).unwrap();
+        let rsa = RsaPssSigner::from_pkcs8_file(testdata.join("rsa_2048.pem")).unwrap();
+        let trusted = TrustedKeys::new().add_key(p256.verifier()).add_key(rsa.verifier());
+
+        for signer in [&p256 as &dyn Signer, &rsa] {
+            let value = C2paBuilder::new(2u32).sign(signer).unwrap();
+            let provenance = value.provenance().clone();
+            assert_eq!(provenance.signature.as_ref().unwrap().algorithm, signer.algorithm());
+            assert!(verify(C2pa::new(2u32, provenance), &trusted).is_ok());
+        }
+
+        // Relabelling the algorithm does not get past the trusted key
+        let mut provenance = C2paBuilder::new(2u32).sign(&p256).unwrap().provenance().clone();
+        provenance.signature.as_mut().unwrap().algorithm = SigningAlg::Ps256;
+        assert!(matches!(
+            verify(C2pa::new(2u32, provenance), &trusted),
+            Err(TransformError::Validation(r)) if r.has(StatusCode::SignatureInvalid)
+        ));
+    }
+
+    #[test]
+    fn test_exclusion_hash_skips_range() {
+        /// Hands out at most three bytes per read.
+        struct Trickle<'a>(&'a [u8]);
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let n = buf.len().min(3).min(self.0.len());
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+
+        let data = b"headerXXXXXXXXXXtrailer";
+        let expected = ContentHash::compute(b"headertrailer");
+        assert_eq!(ContentHash::compute_excluding(HashAlg::Sha256, data, 6, 10).unwrap(), expected);
+        let streamed =
+            ContentHash::from_reader_excluding(HashAlg::Sha256, Trickle(data), 6, 10).unwrap();
+        assert_eq!(streamed, expected);
+
+        let whole = ContentHash::compute_excluding(HashAlg::Sha256, data, 23, 0).unwrap();
+        assert_eq!(whole, ContentHash::compute(data));
+        assert!(ContentHash::compute_excluding(HashAlg::Sha256, data, 20, 4).is_err());
+        assert!(ContentHash::compute_excluding(HashAlg::Sha256, data, u64::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn test_box_binding_leaves_manifest_region_out() {
+        let signer = test_signer(4);
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+        let mut asset = b"header".to_vec();
+        asset.extend([0u8; 16]);
+        asset.extend(b"trailer");
+
+        let value = C2paBuilder::new(asset.clone())
+            .hash_alg(HashAlg::Sha384)
+            .exclude(6, 16)
+            .sign(&signer)
+            .unwrap();
+        let provenance = value.provenance().clone();
+        assert!(matches!(
+            &provenance.asset_binding,
+            AssetBinding::Box { offset: 6, length: 16, hash } if hash.alg() == HashAlg::Sha384
+        ));
+
+        // Writing the manifest into the reserved region keeps the binding
+        let mut embedded = asset.clone();
+        embedded[6..22].copy_from_slice(&provenance.claim_hash.as_bytes()[..16]);
+        assert!(verify(C2pa::new(embedded.clone(), provenance.clone()), &trusted).is_ok());
+
+        // Edits outside it, or content too short to hold it, do not
+        let mut edited = embedded.clone();
+        edited[0] ^= 1;
+        let truncated = embedded[..20].to_vec();
+        for payload in [edited, truncated] {
+            assert!(matches!(
+                verify(C2pa::new(payload, provenance.clone()), &trusted),
+                Err(TransformError::Validation(r)) if r.has(StatusCode::BindingMismatch)
+            ));
+        }
+
+        // Structured payloads have no byte ranges to exclude
+        assert!(C2paBuilder::new(7u32).exclude(0, 1).sign(&signer).is_err());
+    }
+
+    #[test]
+    fn test_verify_cose_signed_claim() {
+        let signer = test_signer(4);
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+        let value = C2paBuilder::new(2u32).cose_sign1(true).sign(&signer).unwrap();
+        let provenance = value.provenance().clone();
+        assert!(verify(C2pa::new(2u32, provenance.clone()), &trusted).is_ok());
+
+        // Dropping the envelope leaves a signature over different bytes
+        let mut stripped = provenance.clone();
+        stripped.signature.as_mut().unwrap().protected_header = None;
+        assert!(matches!(
+            verify(C2pa::new(2u32, stripped), &trusted),
+            Err(TransformError::Validation(r)) if r.has(StatusCode::SignatureInvalid)
+        ));
+
+        // The protected header must describe the recorded signature
+        let mut relabelled = provenance;
+        relabelled.signature.as_mut().unwrap().protected_header =
+            Some(cose::protected_header(SigningAlg::Es256, &signer.key_id(), &[]).unwrap());
+        assert!(matches!(
+            verify(C2pa::new(2u32, relabelled), &trusted),
+            Err(TransformError::Validation(r)) if r.has(StatusCode::SignatureInvalid)
+        ));
+    }
+
+    fn timestamped_unverified(
+        signer: &Ed25519Signer,
+        tsa: LocalTimestampAuthority,
+    ) -> C2pa<u32, Unverified> {
+        let value = C2paBuilder::new(2u32)
+            .timestamp_authority(tsa)
+            .require_timestamp(true)
+            .sign(signer)
+            .unwrap();
+        C2pa::new(*value.payload(), value.provenance().clone())
+    }
+
+    fn local_tsa(seed: u8) -> LocalTimestampAuthority {
+        LocalTimestampAuthority::new(test_signer(seed)).with_fixed_time(1_700_000_000)
+    }
+
+    #[test]
+    fn test_verify_checks_timestamp() {
+        let signer = test_signer(4);
+        let trusted = TrustedKeys::new()
+            .add_key(signer.verifier())
+            .add_timestamp_authority(local_tsa(10).verifier())
+            .require_timestamp(true);
+
+        let value = timestamped_unverified(&signer, local_tsa(10));
+        let token = value.provenance().signature.as_ref().unwrap().timestamp.clone().unwrap();
+        assert_eq!(token.time, 1_700_000_000);
+        assert!(verify(value, &trusted).is_ok());
+
+        // Issued by an authority the verifier does not trust
+        let report = |value| match verify(value, &trusted) {
+            Err(TransformError::Validation(report)) => report,
+            other => panic!("expected a validation report, got {:?}", other.map(|_| ())),
+        };
+        let foreign = timestamped_unverified(&signer, local_tsa(11));
+        assert!(report(foreign).has(StatusCode::TimestampUntrusted));
+
+        // Backdated token
+        let mut backdated = timestamped_unverified(&signer, local_tsa(10));
+        backdated.provenance.signature.as_mut().unwrap().timestamp.as_mut().unwrap().time -= 86_400;
+        assert!(report(backdated).has(StatusCode::TimestampMismatch));
+
+        // Token lifted from another signature
+        let mut replayed = signed_unverified(&signer);
+        replayed.provenance.signature.as_mut().unwrap().timestamp = Some(token);
+        assert!(report(replayed).has(StatusCode::TimestampMismatch));
+
+        // No timestamp at all
+        assert!(report(signed_unverified(&signer)).has(StatusCode::TimestampMissing));
+    }
+
+    #[test]
+    fn test_timestamp_authority_failure() {
+        struct OfflineAuthority;
+
+        impl TimestampAuthority for OfflineAuthority {
+            fn timestamp(&self, _message: &[u8]) -> Result<TimestampToken, TransformError> {
+                Err(TransformError::Signing("tsa offline".into()))
+            }
+        }
+
+        // Optional: signed without a timestamp
+        let value = C2paBuilder::new(1u32)
+            .timestamp_authority(OfflineAuthority)
+            .sign(&TestSigner)
+            .unwrap();
+        assert!(value.provenance().signature.as_ref().unwrap().timestamp.is_none());
+
+        // Required: signing fails
+        let result = C2paBuilder::new(1u32)
+            .timestamp_authority(OfflineAuthority)
+            .require_timestamp(true)
+            .sign(&TestSigner);
+        assert!(matches!(result, Err(TransformError::Signing(_))));
+    }
+
+    /// Root CA and a generator certificate it issued, valid in [100, 200].
+    fn generator_pki() -> (Certificate, Ed25519Signer) {
+        let root_key = test_signer(30);
+        let root = CertificateBuilder::new("root")
+            .ca(None)
+            .public_key(SigningAlg::Ed25519, &root_key.verifier().public_key_bytes())
+            .self_signed(&root_key)
+            .unwrap();
+        let key = test_signer(31);
+        let leaf = CertificateBuilder::new("generator")
+            .validity(100, 200)
+            .public_key(SigningAlg::Ed25519, &key.verifier().public_key_bytes())
+            .issue(&root, &root_key)
+            .unwrap();
+        (root, key.with_certificate_chain(vec![leaf.to_bytes()]))
+    }
+
+    #[test]
+    fn test_verify_through_certificate_chain() {
+        let (root, signer) = generator_pki();
+        let value = signed_unverified(&signer);
+
+        let anchored = TrustedKeys::new()
+            .with_trust_store(TrustStore::new().add_anchor(root))
+            .at_time(150);
+        assert!(verify(value.clone(), &anchored).is_ok());
+
+        // Intact but nobody vouches for the generator
+        let untrusted = TrustedKeys::new().at_time(150);
+        let err = verify_claim(value.provenance(), &untrusted).unwrap_err();
+        let TransformError::Validation(report) = err else {
+            panic!("expected a validation report");
+        };
+        assert_eq!(report.trust(), Trust::Untrusted);
+
+        // Outside the certificate's validity period
+        let expired = verify_claim(value.provenance(), &anchored.at_time(201)).unwrap_err();
+        assert!(matches!(
+            expired,
+            TransformError::Validation(r)
+                if r.has(StatusCode::CertificateExpired) && r.trust() == Trust::Invalid
+        ));
+    }
+
+    #[test]
+    fn test_certificate_checked_at_timestamp_time() {
+        let (root, signer) = generator_pki();
+        let tsa = local_tsa(10).with_fixed_time(150);
+        let trusted = TrustedKeys::new()
+            .with_trust_store(TrustStore::new().add_anchor(root))
+            .add_timestamp_authority(tsa.verifier());
+
+        // Signed while the certificate was valid; verified long after it expired
+        assert!(verify(timestamped_unverified(&signer, tsa), &trusted).is_ok());
+        assert!(verify(signed_unverified(&signer), &trusted).is_err());
+    }
+
+    #[test]
+    fn test_leaf_certificate_must_hold_signing_key() {
+        let (root, signer) = generator_pki();
+        let other = test_signer(32).with_certificate_chain(signer.certificate_chain().to_vec());
+        let trusted = TrustedKeys::new()
+            .with_trust_store(TrustStore::new().add_anchor(root))
+            .at_time(150);
+
+        let err = verify(signed_unverified(&other), &trusted).unwrap_err();
+        assert!(matches!(
+            err,
+            TransformError::Validation(r) if r.has(StatusCode::CertificateInvalid)
+        ));
+    }
+
+    #[test]
+    fn test_sign_invokes_signer_over_claim() {
+        use std::cell::RefCell;
+
+        struct RecordingSigner(RefCell<Vec<u8>>);
+
+        impl Signer for RecordingSigner {
+            fn sign(&self, data: &[u8]) -> Result<Vec<u8>, TransformError> {
+                *self.0.borrow_mut() = data.to_vec();
+                Ok(vec![0u8; 64])
+            }
+
+            fn certificate_chain(&self) -> &[Vec<u8>] {
+                &[]
+            }
+        }
+
+        let signer = RecordingSigner(RefCell::new(Vec::new()));
+        let value = C2paBuilder::new(7u32).sign(&signer).unwrap();
+
+        // The signed bytes are exactly what the claim hash commits to
+        let signed: [u8; 32] = Sha256::digest(&*signer.0.borrow()).into();
+        assert_eq!(value.provenance().claim_hash.as_bytes(), signed);
+    }
+
+    #[test]
+    fn test_signer_error_aborts_signing() {
+        struct FailingSigner;
+
+        impl Signer for FailingSigner {
+            fn sign(&self, _data: &[u8]) -> Result<Vec<u8>, TransformError> {
+                Err(TransformError::Signing("hsm offline".into()))
+            }
+
+            fn certificate_chain(&self) -> &[Vec<u8>] {
+                &[]
+            }
+        }
+
+        let result = C2paBuilder::new(7u32).sign(&FailingSigner);
+        assert!(matches!(result, Err(TransformError::Signing(_))));
+    }
+
+    #[test]
+    fn test_signer_defaults_without_identity_methods() {
+        // Implements only the methods `Signer` originally required
+        struct ChainSigner(Vec<Vec<u8>>);
+
+        impl Signer for ChainSigner {
+            fn sign(&self, _data: &[u8]) -> Result<Vec<u8>, TransformError> {
+                Ok(vec![0u8; 64])
+            }
+
+            fn certificate_chain(&self) -> &[Vec<u8>] {
+                &self.0
+            }
+        }
+
+        let key = test_signer(3);
+        let leaf = CertificateBuilder::new("leaf")
+            .public_key(SigningAlg::Ed25519, &key.verifier().public_key_bytes())
+            .self_signed(&key)
+            .unwrap();
+        let signer = ChainSigner(vec![leaf.to_bytes()]);
+        assert_eq!(signer.algorithm(), SigningAlg::Placeholder);
+        assert_eq!(signer.key_id(), leaf.key_id());
+        assert!(ChainSigner(Vec::new()).key_id().is_empty());
+        assert!(ChainSigner(vec![b"not a certificate".to_vec()]).key_id().is_empty());
+    }
+
+    #[test]
+    fn test_signature_block_recorded_in_provenance() {
+        let signer = test_signer(3).with_certificate_chain(vec![b"leaf-cert".to_vec()]);
+        let value = C2paBuilder::new(7u32).sign(&signer).unwrap();
+
+        let block = value.provenance().signature.as_ref().unwrap();
+        assert_eq!(block.algorithm, SigningAlg::Ed25519);
+        assert_eq!(block.signature.len(), 64);
+        assert_eq!(block.key_id, signer.key_id());
+        assert_eq!(block.certificate_chain, vec![b"leaf-cert".to_vec()]);
+    }
+
     // Macro-generated transform tests are in tests/macro_tests.rs
     // (integration tests can use the crate as external dependency)
 }
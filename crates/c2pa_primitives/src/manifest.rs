@@ -0,0 +1,433 @@
+//! Persistent manifests: export provenance to disk and load it back.
+//!
+//! A manifest file is a self-describing CBOR map:
+//!
+//! ```text
+//! {
+//!   "format":     "c2pa_primitives/manifest",
+//!   "version":    1,
+//!   "provenance": {
+//!     "manifest_id": tstr,
+//!     "claim_hash":  bstr,
+//!     ? "alg":       tstr,           ; claim hash algorithm, omitted for SHA-256
+//!     "claim":       claim,          ; canonical claim, see `claim` module
+//!     ? "signature": { "alg": tstr, "signature": bstr, "key_id": bstr,
+//!                      "certificate_chain": [* bstr],
+//!                      ? "timestamp": timestamp,     ; see `timestamp` module
+//!                      ? "protected": bstr },        ; COSE header, see `cose` module
+//!   },
+//!   ? "payload":  bstr,
+//! }
+//! ```
+//!
+//! Loading always yields `C2pa<T, Unverified>`; the only way back to
+//! `Verified` is through [`verify`](crate::verify).
+
+use std::path::Path;
+
+use crate::cbor::Value;
+use crate::claim::{self, array, bytes, field, text};
+use crate::timestamp;
+use crate::{C2pa, ClaimSignature, Image, Invoice, Provenance, TransformError, Unverified};
+
+/// Format label stored in every manifest file.
+pub const MANIFEST_FORMAT: &str = "c2pa_primitives/manifest";
+
+/// Version of the manifest file layout.
+pub const MANIFEST_FORMAT_VERSION: u64 = 1;
+
+/// Provenance exported from a `C2pa<T, S>`, optionally with its payload.
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    pub provenance: Provenance,
+    /// Encoded payload (see [`C2paPayload`]), if it was exported.
+    pub payload: Option<Vec<u8>>,
+}
+
+impl Manifest {
+    /// Export only the provenance of a value.
+    pub fn from_value<T, S>(value: &C2pa<T, S>) -> Self {
+        Self {
+            provenance: value.provenance().clone(),
+            payload: None,
+        }
+    }
+
+    /// Export the provenance together with the encoded payload.
+    pub fn with_payload<T: C2paPayload, S>(value: &C2pa<T, S>) -> Self {
+        Self {
+            provenance: value.provenance().clone(),
+            payload: Some(value.payload().to_payload_bytes()),
+        }
+    }
+
+    /// Serialize to the manifest file format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut entries = vec![
+            (Value::text("format"), Value::text(MANIFEST_FORMAT)),
+            (Value::text("version"), Value::uint(MANIFEST_FORMAT_VERSION)),
+            (Value::text("provenance"), provenance_value(&self.provenance)),
+        ];
+        if let Some(payload) = &self.payload {
+            entries.push((Value::text("payload"), Value::bytes(payload.clone())));
+        }
+        Value::Map(entries).to_bytes()
+    }
+
+    /// Parse the manifest file format.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, TransformError> {
+        let value = Value::from_bytes(data)
+            .map_err(|e| TransformError::Manifest(format!("invalid CBOR: {}", e)))?;
+
+        if text(&value, "format")? != MANIFEST_FORMAT {
+            return Err(TransformError::Manifest("not a c2pa_primitives manifest".into()));
+        }
+        let version = claim::uint(&value, "version")?;
+        if version != MANIFEST_FORMAT_VERSION {
+            return Err(TransformError::Manifest(format!(
+                "unsupported manifest version {}",
+                version
+            )));
+        }
+
+        let payload = match value.get("payload") {
+            Some(payload) => Some(
+                payload
+                    .as_bytes()
+                    .ok_or_else(|| claim::malformed("payload"))?
+                    .to_vec(),
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            provenance: decode_provenance(field(&value, "provenance")?)?,
+            payload,
+        })
+    }
+
+    /// Write the manifest to a file.
+    pub fn write_to(&self, path: impl AsRef<Path>) -> Result<(), TransformError> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_bytes())
+            .map_err(|e| TransformError::Manifest(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Read a manifest from a file.
+    pub fn read_from(path: impl AsRef<Path>) -> Result<Self, TransformError> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)
+            .map_err(|e| TransformError::Manifest(format!("{}: {}", path.display(), e)))?;
+        Self::from_bytes(&data)
+    }
+
+    /// Pair the provenance with a payload supplied by the caller.
+    pub fn into_unverified<T>(self, payload: T) -> C2pa<T, Unverified> {
+        C2pa::new(payload, self.provenance)
+    }
+
+    /// Decode the embedded payload and pair it with the provenance.
+    pub fn into_unverified_payload<T: C2paPayload>(
+        self,
+    ) -> Result<C2pa<T, Unverified>, TransformError> {
+        let data = self
+            .payload
+            .as_deref()
+            .ok_or_else(|| TransformError::Manifest("manifest has no payload".into()))?;
+        let payload = T::from_payload_bytes(data)?;
+        Ok(C2pa::new(payload, self.provenance))
+    }
+}
+
+fn provenance_value(provenance: &Provenance) -> Value {
+    let mut entries = vec![
+        (Value::text("manifest_id"), Value::text(&provenance.manifest_id)),
+        (Value::text("claim_hash"), Value::bytes(provenance.claim_hash.as_bytes())),
+        (
+            Value::text("claim"),
+            claim::claim_value(
+                &provenance.generator,
+                &provenance.asset_binding,
+                &provenance.ingredients,
+                &provenance.assertions,
+            ),
+        ),
+    ];
+    if let Some(signature) = &provenance.signature {
+        let chain = signature
+            .certificate_chain
+            .iter()
+            .map(|cert| Value::bytes(cert.clone()))
+            .collect();
+        let mut fields = vec![
+            (Value::text("alg"), Value::text(signature.algorithm.as_str())),
+            (Value::text("signature"), Value::bytes(signature.signature.clone())),
+            (Value::text("key_id"), Value::bytes(signature.key_id.clone())),
+            (Value::text("certificate_chain"), Value::Array(chain)),
+        ];
+        if let Some(token) = &signature.timestamp {
+            fields.push((Value::text("timestamp"), timestamp::token_value(token)));
+        }
+        if let Some(protected) = &signature.protected_header {
+            fields.push((Value::text("protected"), Value::bytes(protected.clone())));
+        }
+        entries.push((Value::text("signature"), Value::Map(fields)));
+    }
+    claim::with_hash_alg(Value::Map(entries), provenance.claim_hash.alg())
+}
+
+fn decode_provenance(value: &Value) -> Result<Provenance, TransformError> {
+    let parts = claim::decode_claim(field(value, "claim")?)?;
+
+    let signature = match value.get("signature") {
+        Some(signature) => Some(ClaimSignature {
+            algorithm: text(signature, "alg")?.parse()?,
+            signature: bytes(signature, "signature")?.to_vec(),
+            key_id: bytes(signature, "key_id")?.to_vec(),
+            certificate_chain: array(signature, "certificate_chain")?
+                .iter()
+                .map(|cert| {
+                    cert.as_bytes()
+                        .map(<[u8]>::to_vec)
+                        .ok_or_else(|| claim::malformed("certificate_chain"))
+                })
+                .collect::<Result<_, _>>()?,
+            timestamp: signature
+                .get("timestamp")
+                .map(timestamp::decode_token)
+                .transpose()?,
+            protected_header: signature
+                .get("protected")
+                .map(|protected| {
+                    protected
+                        .as_bytes()
+                        .map(<[u8]>::to_vec)
+                        .ok_or_else(|| claim::malformed("protected"))
+                })
+                .transpose()?,
+        }),
+        None => None,
+    };
+
+    Ok(Provenance {
+        manifest_id: text(value, "manifest_id")?.to_string(),
+        claim_hash: claim::claim_hash(value, "claim_hash")?,
+        asset_binding: parts.binding,
+        ingredients: parts.ingredients,
+        generator: parts.generator,
+        assertions: parts.assertions,
+        signature,
+    })
+}
+
+// ============================================================================
+// Trait: C2paPayload - Payloads that can travel inside a manifest
+// ============================================================================
+
+/// Payloads that can be stored in and restored from a manifest file.
+pub trait C2paPayload: Sized {
+    fn to_payload_bytes(&self) -> Vec<u8>;
+    fn from_payload_bytes(bytes: &[u8]) -> Result<Self, TransformError>;
+}
+
+impl C2paPayload for Vec<u8> {
+    fn to_payload_bytes(&self) -> Vec<u8> {
+        self.clone()
+    }
+
+    fn from_payload_bytes(bytes: &[u8]) -> Result<Self, TransformError> {
+        Ok(bytes.to_vec())
+    }
+}
+
+impl C2paPayload for String {
+    fn to_payload_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_payload_bytes(bytes: &[u8]) -> Result<Self, TransformError> {
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| TransformError::Manifest("payload is not valid UTF-8".into()))
+    }
+}
+
+macro_rules! impl_payload_for_primitive {
+    ($($ty:ty),*) => {
+        $(
+            impl C2paPayload for $ty {
+                fn to_payload_bytes(&self) -> Vec<u8> {
+                    self.to_le_bytes().to_vec()
+                }
+
+                fn from_payload_bytes(bytes: &[u8]) -> Result<Self, TransformError> {
+                    let bytes = bytes.try_into().map_err(|_| {
+                        TransformError::Manifest(
+                            concat!("payload is not a ", stringify!($ty)).into(),
+                        )
+                    })?;
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_payload_for_primitive!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl C2paPayload for Invoice {
+    fn to_payload_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+
+    fn from_payload_bytes(bytes: &[u8]) -> Result<Self, TransformError> {
+        Invoice::from_bytes(bytes)
+    }
+}
+
+impl C2paPayload for Image {
+    fn to_payload_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(8 + self.pixels.len());
+        data.extend_from_slice(&self.width.to_le_bytes());
+        data.extend_from_slice(&self.height.to_le_bytes());
+        data.extend_from_slice(&self.pixels);
+        data
+    }
+
+    fn from_payload_bytes(bytes: &[u8]) -> Result<Self, TransformError> {
+        if bytes.len() < 8 {
+            return Err(TransformError::Manifest("truncated image payload".into()));
+        }
+        let width = u32::from_le_bytes(bytes[0..4].try_into().unwrap());
+        let height = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+        let pixels = bytes[8..].to_vec();
+        if pixels.len() as u64 != width as u64 * height as u64 {
+            return Err(TransformError::Manifest("image size mismatch".into()));
+        }
+        Ok(Image { width, height, pixels })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_signer;
+    use crate::*;
+
+    fn signed_invoice(signer: &Ed25519Signer) -> C2pa<Invoice, Verified> {
+        let bytes = C2paBuilder::new(b"7:250".to_vec()).sign(signer).unwrap();
+        C2paBuilder::new(Invoice { id: 7, amount: 250 })
+            .generator("billing/1.0")
+            .add_ingredient(&bytes, IngredientRelation::DerivedFrom)
+            .add_assertion(CustomAssertion::json("c2pa.test", r#"{"ok":true}"#))
+            .sign(signer)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_file_roundtrip_then_verify() {
+        let signer = test_signer(6);
+        let original = signed_invoice(&signer);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invoice.c2pa");
+
+        Manifest::with_payload(&original).write_to(&path).unwrap();
+        let loaded: C2pa<Invoice, Unverified> = Manifest::read_from(&path)
+            .unwrap()
+            .into_unverified_payload()
+            .unwrap();
+
+        assert_eq!(loaded.provenance().claim_hash, original.provenance().claim_hash);
+        assert_eq!(loaded.provenance().ingredients.len(), 1);
+
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+        let verified = verify(loaded, &trusted).unwrap();
+        assert_eq!(verified.payload(), original.payload());
+    }
+
+    #[test]
+    fn test_provenance_only_export() {
+        let signer = test_signer(6);
+        let original = signed_invoice(&signer);
+        let manifest = Manifest::from_bytes(&Manifest::from_value(&original).to_bytes()).unwrap();
+        assert!(manifest.payload.is_none());
+
+        // Caller supplies the payload out of band
+        let loaded = manifest.into_unverified(original.payload().clone());
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+        assert!(verify(loaded, &trusted).is_ok());
+    }
+
+    #[test]
+    fn test_timestamp_survives_roundtrip() {
+        let signer = test_signer(6);
+        let tsa = LocalTimestampAuthority::new(test_signer(9));
+        let trusted = TrustedKeys::new()
+            .add_key(signer.verifier())
+            .add_timestamp_authority(tsa.verifier())
+            .require_timestamp(true);
+        let original = C2paBuilder::new(5u32)
+            .timestamp_authority(tsa)
+            .sign(&signer)
+            .unwrap();
+
+        let bytes = Manifest::with_payload(&original).to_bytes();
+        let loaded: C2pa<u32, Unverified> =
+            Manifest::from_bytes(&bytes).unwrap().into_unverified_payload().unwrap();
+        let token = |p: &Provenance| p.signature.as_ref().unwrap().timestamp.clone();
+        assert_eq!(token(loaded.provenance()), token(original.provenance()));
+        assert!(verify(loaded, &trusted).is_ok());
+    }
+
+    #[test]
+    fn test_hash_alg_survives_roundtrip() {
+        let signer = test_signer(6);
+        let original = C2paBuilder::new(5u32)
+            .hash_alg(HashAlg::Sha512)
+            .sign(&signer)
+            .unwrap();
+
+        let bytes = Manifest::with_payload(&original).to_bytes();
+        let loaded: C2pa<u32, Unverified> =
+            Manifest::from_bytes(&bytes).unwrap().into_unverified_payload().unwrap();
+        assert_eq!(loaded.provenance().claim_hash, original.provenance().claim_hash);
+        assert_eq!(loaded.provenance().claim_hash.alg(), HashAlg::Sha512);
+
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+        assert!(verify(loaded, &trusted).is_ok());
+    }
+
+    #[test]
+    fn test_cose_signature_survives_roundtrip() {
+        let signer = test_signer(6);
+        let original = C2paBuilder::new(5u32).cose_sign1(true).sign(&signer).unwrap();
+
+        let bytes = Manifest::with_payload(&original).to_bytes();
+        let loaded: C2pa<u32, Unverified> =
+            Manifest::from_bytes(&bytes).unwrap().into_unverified_payload().unwrap();
+        let protected = |p: &Provenance| p.signature.as_ref().unwrap().protected_header.clone();
+        assert!(protected(loaded.provenance()).is_some());
+        assert_eq!(protected(loaded.provenance()), protected(original.provenance()));
+
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+        assert!(verify(loaded, &trusted).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_foreign_or_corrupt_files() {
+        let foreign = Value::map([("format", Value::text("other")), ("version", Value::uint(1))]);
+        assert!(Manifest::from_bytes(&foreign.to_bytes()).is_err());
+
+        let mut bytes = Manifest::from_value(&signed_invoice(&test_signer(6))).to_bytes();
+        bytes.truncate(bytes.len() / 2);
+        assert!(Manifest::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_primitive_payload_roundtrip() {
+        assert_eq!(u32::from_payload_bytes(&42u32.to_payload_bytes()).unwrap(), 42);
+        assert!(u32::from_payload_bytes(&[1, 2]).is_err());
+
+        let image = Image::test_pattern(3, 2);
+        assert_eq!(Image::from_payload_bytes(&image.to_payload_bytes()).unwrap(), image);
+    }
+}
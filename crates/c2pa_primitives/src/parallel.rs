@@ -0,0 +1,201 @@
+//! Running pipeline steps on worker threads.
+//!
+//! Pipeline contexts live on a per-thread stack, so a `_c2pa` wrapper called
+//! on a freshly spawned thread has no context. A [`PipelineHandle`] captures
+//! the active context and re-enters it on any thread; [`par_map`] uses it to
+//! fan chunks of a verified `Vec` out over scoped worker threads.
+//!
+//! Parameter openings recorded while a handle is entered are kept on the
+//! handle; [`par_map`] hands them back to the calling pipeline.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::{
+    current_ctx, lock_sink, transform_helper, with_pushed_ctx, C2pa, C2paBindable, ClaimHash,
+    IngredientRelation, OpeningSink, ParamOpening, TransformContext, TransformError, Verified,
+};
+
+/// `Send + Sync` handle to a pipeline context.
+///
+/// Cloning is cheap; each [`enter`](Self::enter) runs with its own copy of
+/// the context captured by [`current`](Self::current), so workers cannot
+/// disturb each other's transform metadata. The openings they record are
+/// shared by all clones until [taken](Self::take_param_openings).
+///
+/// ```
+/// use c2pa_primitives::*;
+///
+/// with_new_ctx("batch", || {
+///     let handle = PipelineHandle::current().unwrap();
+///     std::thread::scope(|scope| {
+///         scope.spawn(|| {
+///             handle.enter(|| assert_eq!(with_ctx(|ctx| ctx.generator.clone()), "batch"));
+///         });
+///     });
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct PipelineHandle {
+    ctx: Arc<TransformContext>,
+    openings: OpeningSink,
+}
+
+impl PipelineHandle {
+    /// Capture the innermost active pipeline context.
+    pub fn current() -> Option<Self> {
+        current_ctx().map(Self::from_context)
+    }
+
+    /// Handle for an explicitly constructed context.
+    pub fn from_context(ctx: TransformContext) -> Self {
+        Self {
+            ctx: Arc::new(ctx),
+            openings: OpeningSink::default(),
+        }
+    }
+
+    /// The captured context.
+    pub fn context(&self) -> &TransformContext {
+        &self.ctx
+    }
+
+    /// Run the closure on this thread inside the captured context.
+    pub fn enter<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let (result, mut ctx) = with_pushed_ctx((*self.ctx).clone(), f);
+        lock_sink(&self.openings).extend(ctx.take_param_openings());
+        result
+    }
+
+    /// Remove and return the openings recorded inside [`enter`](Self::enter).
+    pub fn take_param_openings(&self) -> Vec<(ClaimHash, ParamOpening)> {
+        std::mem::take(&mut *lock_sink(&self.openings))
+    }
+}
+
+/// Apply `f` to chunks of a verified `Vec` in parallel.
+///
+/// The input is split into chunks of at most `chunk_size` items. Each chunk
+/// is signed as a verified value derived from `input` (transform
+/// `par_map.chunk`), then handed to `f` on a scoped worker thread running in
+/// the current pipeline context, so `f` can call `_c2pa` wrappers. Results
+/// are returned in chunk order. Openings recorded by `f` are handed to the
+/// current pipeline.
+///
+/// Must be called inside a pipeline.
+pub fn par_map<T, U, F>(
+    input: &C2pa<Vec<T>, Verified>,
+    chunk_size: usize,
+    f: F,
+) -> Result<Vec<C2pa<U, Verified>>, TransformError>
+where
+    T: Clone + Send + Sync,
+    Vec<T>: C2paBindable,
+    U: Send,
+    F: Fn(&C2pa<Vec<T>, Verified>) -> Result<C2pa<U, Verified>, TransformError> + Sync,
+{
+    if chunk_size == 0 {
+        return Err(TransformError::C2pa("par_map chunk size must be non-zero".into()));
+    }
+    let handle = PipelineHandle::current()
+        .ok_or_else(|| TransformError::C2pa("par_map called outside #[c2pa_pipeline]".into()))?;
+
+    // Chunks are signed up front so every worker input is itself verified
+    let chunks = handle.enter(|| {
+        input
+            .payload()
+            .chunks(chunk_size)
+            .map(|chunk| {
+                crate::with_ctx(|ctx| {
+                    transform_helper::build_transform_result(
+                        chunk.to_vec(),
+                        input,
+                        "par_map.chunk",
+                        IngredientRelation::DerivedFrom,
+                        Vec::new(),
+                        ctx,
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(chunks.len());
+    let next = AtomicUsize::new(0);
+
+    // Workers pull chunk indices until none are left
+    let mut results: Vec<_> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    handle.enter(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(chunk) = chunks.get(index) else {
+                                break done;
+                            };
+                            done.push((index, f(chunk)));
+                        }
+                    })
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| match worker.join() {
+                Ok(done) => done,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect()
+    });
+
+    let openings = handle.take_param_openings();
+    crate::with_ctx(|ctx| ctx.param_openings.extend(openings));
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_handle_is_send_sync() {
+        assert_send_sync::<PipelineHandle>();
+    }
+
+    #[test]
+    fn test_handle_enters_on_other_thread() {
+        let handle = with_new_ctx("captured", || PipelineHandle::current().unwrap());
+        assert!(!has_ctx());
+
+        let generator = std::thread::spawn(move || {
+            assert!(!has_ctx());
+            let generator = handle.enter(|| with_ctx(|ctx| ctx.generator.clone()));
+            assert!(!has_ctx());
+            generator
+        })
+        .join()
+        .unwrap();
+        assert_eq!(generator, "captured");
+    }
+
+    #[test]
+    fn test_par_map_requires_pipeline() {
+        let input = C2paBuilder::new(vec![1u8, 2, 3]).sign(&TestSigner).unwrap();
+        let result = par_map(&input, 2, |chunk| {
+            C2paBuilder::new(chunk.payload().len() as u32).sign(&TestSigner)
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! Manifests embedded in PNG files.
+//!
+//! The manifest store (see [`ManifestStore`]) travels in a `caBX` chunk
+//! right after `IHDR`, where C2PA places it in PNG files:
+//!
+//! ```text
+//! signature | IHDR | caBX: manifest store | ... | IEND
+//! ```
+//!
+//! The asset binding is a data hash of the file with the `caBX` chunk left
+//! out, so writing the manifest into the file does not change what it
+//! binds. [`extract_png`] hands back exactly those bytes as the payload.
+
+use crate::{AssetBinding, C2pa, ContentHash, HashAlg, ManifestStore, TransformError, Unverified};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+const MANIFEST_CHUNK: [u8; 4] = *b"caBX";
+
+/// PNG chunk lengths are limited to 2^31 - 1.
+const MAX_CHUNK_LEN: usize = i32::MAX as usize;
+
+fn malformed(what: &str) -> TransformError {
+    TransformError::Manifest(format!("invalid PNG: {}", what))
+}
+
+/// Embed the manifest of `value` in the PNG image that is its payload.
+///
+/// The manifest must bind the image through [`png_binding`]; an existing
+/// manifest chunk is replaced.
+pub fn embed_png<S>(value: &C2pa<Vec<u8>, S>) -> Result<Vec<u8>, TransformError> {
+    let png = Png::parse(value.payload())?;
+    let alg = match &value.provenance().asset_binding {
+        AssetBinding::Hash(hash) => hash.alg(),
+        AssetBinding::Box { .. } => {
+            return Err(TransformError::Binding("PNG manifests use a data-hash binding".into()))
+        }
+    };
+    let image = png.without_manifest();
+    if AssetBinding::Hash(ContentHash::compute_with(alg, &image))
+        != value.provenance().asset_binding
+    {
+        return Err(TransformError::Binding("manifest does not bind this PNG image".into()));
+    }
+
+    let store = ManifestStore::from_value(value).to_bytes()?;
+    if store.len() > MAX_CHUNK_LEN {
+        return Err(TransformError::Manifest("manifest store is too large for a PNG chunk".into()));
+    }
+    let mut out = Vec::with_capacity(image.len() + store.len() + 12);
+    out.extend_from_slice(&image[..png.ihdr_end]);
+    write_chunk(&mut out, &MANIFEST_CHUNK, &store);
+    out.extend_from_slice(&image[png.ihdr_end..]);
+    Ok(out)
+}
+
+/// Read the manifest embedded in a PNG file.
+///
+/// The payload is the file without its manifest chunk, ready for
+/// [`verify`](crate::verify).
+pub fn extract_png(data: &[u8]) -> Result<C2pa<Vec<u8>, Unverified>, TransformError> {
+    let png = Png::parse(data)?;
+    let store = png
+        .manifest
+        .ok_or_else(|| TransformError::Manifest("PNG has no embedded manifest".into()))?;
+    ManifestStore::from_bytes(store)?.into_unverified(png.without_manifest())
+}
+
+/// Data-hash binding of a PNG image, leaving out any manifest chunk.
+pub fn png_binding(data: &[u8], alg: HashAlg) -> Result<AssetBinding, TransformError> {
+    let image = Png::parse(data)?.without_manifest();
+    Ok(AssetBinding::Hash(ContentHash::compute_with(alg, image)))
+}
+
+/// A PNG file split around its manifest chunk.
+struct Png<'a> {
+    data: &'a [u8],
+    /// End of the `IHDR` chunk, where the manifest chunk goes.
+    ihdr_end: usize,
+    /// Byte range of the `caBX` chunk.
+    manifest_chunk: Option<std::ops::Range<usize>>,
+    /// Contents of the `caBX` chunk.
+    manifest: Option<&'a [u8]>,
+}
+
+impl<'a> Png<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, TransformError> {
+        if !data.starts_with(&SIGNATURE) {
+            return Err(malformed("missing signature"));
+        }
+        let mut png = Self {
+            data,
+            ihdr_end: 0,
+            manifest_chunk: None,
+            manifest: None,
+        };
+        let mut pos = SIGNATURE.len();
+        loop {
+            let header = data
+                .get(pos..pos + 8)
+                .ok_or_else(|| malformed("truncated chunk"))?;
+            let len = u32::from_be_bytes(header[..4].try_into().expect("four bytes")) as usize;
+            let chunk_type: [u8; 4] = header[4..].try_into().expect("four bytes");
+            if len > MAX_CHUNK_LEN || data.len() - pos - 8 < len + 4 {
+                return Err(malformed("truncated chunk"));
+            }
+            let end = pos + 12 + len;
+            let contents = &data[pos + 8..pos + 8 + len];
+
+            match &chunk_type {
+                b"IHDR" if pos == SIGNATURE.len() => png.ihdr_end = end,
+                _ if pos == SIGNATURE.len() => return Err(malformed("IHDR must come first")),
+                &MANIFEST_CHUNK if png.manifest.is_some() => {
+                    return Err(malformed("more than one manifest chunk"))
+                }
+                &MANIFEST_CHUNK => {
+                    let crc = &data[end - 4..end];
+                    if crc != crc32(&data[pos + 4..end - 4]).to_be_bytes() {
+                        return Err(malformed("manifest chunk CRC mismatch"));
+                    }
+                    png.manifest_chunk = Some(pos..end);
+                    png.manifest = Some(contents);
+                }
+                b"IEND" => return Ok(png),
+                _ => {}
+            }
+            pos = end;
+        }
+    }
+
+    /// The file with the manifest chunk left out.
+    fn without_manifest(&self) -> Vec<u8> {
+        match &self.manifest_chunk {
+            Some(chunk) => [&self.data[..chunk.start], &self.data[chunk.end..]].concat(),
+            None => self.data.to_vec(),
+        }
+    }
+}
+
+fn write_chunk(out: &mut Vec<u8>, chunk_type: &[u8; 4], contents: &[u8]) {
+    let start = out.len();
+    out.extend_from_slice(&(contents.len() as u32).to_be_bytes());
+    out.extend_from_slice(chunk_type);
+    out.extend_from_slice(contents);
+    let crc = crc32(&out[start + 4..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// CRC-32 (ISO 3309) over a chunk's type and contents.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_signer;
+    use crate::*;
+    use std::path::Path;
+
+    fn image() -> Vec<u8> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/gray_4x4.png");
+        std::fs::read(path).unwrap()
+    }
+
+    fn signed_png(signer: &Ed25519Signer) -> Vec<u8> {
+        let value = C2paBuilder::new(image())
+            .cose_sign1(true)
+            .add_assertion(CustomAssertion::json("c2pa.test", r#"{"ok":true}"#))
+            .sign(signer)
+            .unwrap();
+        embed_png(&value).unwrap()
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    }
+
+    #[test]
+    fn test_embed_extract_verify() {
+        let signer = test_signer(7);
+        let signed = signed_png(&signer);
+        let ihdr_end = SIGNATURE.len() + 12 + 13;
+        assert_eq!(signed[ihdr_end + 4..ihdr_end + 8], MANIFEST_CHUNK);
+
+        let extracted = extract_png(&signed).unwrap();
+        assert_eq!(extracted.payload(), &image());
+        assert_eq!(
+            png_binding(&signed, HashAlg::Sha256).unwrap(),
+            extracted.provenance().asset_binding
+        );
+
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+        let verified = verify(extracted, &trusted).unwrap();
+
+        // Re-embedding the verified value reproduces the file
+        assert_eq!(embed_png(&verified).unwrap(), signed);
+    }
+
+    #[test]
+    fn test_edited_pixels_fail_verification() {
+        let signer = test_signer(7);
+        let mut signed = signed_png(&signer);
+        let idat = signed.windows(4).position(|w| w == b"IDAT").unwrap();
+        signed[idat + 6] ^= 0x01;
+
+        let trusted = TrustedKeys::new().add_key(signer.verifier());
+        assert!(matches!(
+            verify(extract_png(&signed).unwrap(), &trusted),
+            Err(TransformError::Validation(r)) if r.has(StatusCode::BindingMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_unbound_or_malformed_files() {
+        // The manifest binds different bytes than the image it goes into
+        let signer = test_signer(7);
+        let value = C2paBuilder::new(b"not the image".to_vec())
+            .cose_sign1(true)
+            .sign(&signer)
+            .unwrap();
+        assert!(embed_png(&value).is_err());
+
+        assert!(extract_png(&image()).is_err());
+        assert!(extract_png(b"GIF89a").is_err());
+        let signed = signed_png(&signer);
+        assert!(extract_png(&signed[..signed.len() - 1]).is_err());
+
+        // A corrupted manifest chunk is caught by its CRC
+        let mut corrupt = signed.clone();
+        corrupt[SIGNATURE.len() + 25 + 20] ^= 0x01;
+        assert!(extract_png(&corrupt).is_err());
+    }
+}
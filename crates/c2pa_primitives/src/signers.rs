@@ -0,0 +1,527 @@
+//! Concrete signature algorithms for [`Signer`] and [`SignatureVerifier`].
+//!
+//! Signers load PKCS#8 private keys, either from memory or from a PEM or
+//! DER file on disk. Each verifier's raw public key encoding is the one
+//! certificates carry and [`key_id_for`] hashes:
+//!
+//! | Algorithm | Public key | Signature |
+//! |-----------|------------|-----------|
+//! | Ed25519 | 32-byte key | 64 bytes |
+//! | ES256 | SEC1 uncompressed point | 64-byte `r \|\| s` |
+//! | PS256 | PKCS#1 `RSAPublicKey` DER | modulus-sized |
+
+use std::path::Path;
+
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::pkcs1::{DecodeRsaPublicKey, EncodeRsaPublicKey};
+use rsa::signature::{RandomizedSigner, SignatureEncoding, Verifier as _};
+use rsa::traits::PublicKeyParts;
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use sha2::Sha256;
+
+use crate::{key_id_for, SignatureVerifier, Signer, SigningAlg, TransformError};
+
+/// Verifier for a raw public key of the given algorithm.
+pub(crate) fn verifier_for(
+    algorithm: SigningAlg,
+    public_key: &[u8],
+) -> Result<Box<dyn SignatureVerifier + Send + Sync>, TransformError> {
+    match algorithm {
+        SigningAlg::Ed25519 => Ok(Box::new(Ed25519Verifier::from_public_key_bytes(public_key)?)),
+        SigningAlg::Es256 => Ok(Box::new(P256Verifier::from_public_key_bytes(public_key)?)),
+        SigningAlg::Ps256 => Ok(Box::new(RsaPssVerifier::from_public_key_bytes(public_key)?)),
+        SigningAlg::Placeholder => Err(TransformError::Verification(
+            "placeholder keys cannot verify signatures".into(),
+        )),
+    }
+}
+
+/// Read a PKCS#8 private key from a PEM or DER file.
+fn read_pkcs8_file<K: DecodePrivateKey>(path: &Path) -> Result<K, TransformError> {
+    let data = std::fs::read(path).map_err(|e| {
+        TransformError::Signing(format!("cannot read key file {}: {}", path.display(), e))
+    })?;
+    let key = match std::str::from_utf8(&data) {
+        Ok(pem) if pem.trim_start().starts_with("-----BEGIN") => K::from_pkcs8_pem(pem),
+        _ => K::from_pkcs8_der(&data),
+    };
+    key.map_err(|e| TransformError::Signing(format!("invalid PKCS#8 key: {}", e)))
+}
+
+// ============================================================================
+// Ed25519
+// ============================================================================
+
+/// Ed25519 signer holding a private key.
+///
+/// # Example
+///
+/// ```
+/// use c2pa_primitives::*;
+///
+/// let signer = Ed25519Signer::from_raw(&[7u8; 32]).unwrap();
+/// let value = C2paBuilder::new(42u32).sign(&signer).unwrap();
+/// assert_eq!(*value.payload(), 42);
+/// ```
+pub struct Ed25519Signer {
+    key: SigningKey,
+    certificate_chain: Vec<Vec<u8>>,
+}
+
+impl Ed25519Signer {
+    /// Load a private key from a PKCS#8 DER document.
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self, TransformError> {
+        let key = SigningKey::from_pkcs8_der(der)
+            .map_err(|e| TransformError::Signing(format!("invalid PKCS#8 key: {}", e)))?;
+        Ok(Self::from_key(key))
+    }
+
+    /// Load a private key from a PKCS#8 PEM or DER file.
+    pub fn from_pkcs8_file(path: impl AsRef<Path>) -> Result<Self, TransformError> {
+        Ok(Self::from_key(read_pkcs8_file(path.as_ref())?))
+    }
+
+    /// Load a private key from raw bytes.
+    ///
+    /// Accepts either the 32-byte secret seed or the 64-byte
+    /// `secret || public` keypair encoding.
+    pub fn from_raw(bytes: &[u8]) -> Result<Self, TransformError> {
+        let key = match bytes.len() {
+            32 => {
+                let mut seed = [0u8; 32];
+                seed.copy_from_slice(bytes);
+                SigningKey::from_bytes(&seed)
+            }
+            64 => {
+                let mut keypair = [0u8; 64];
+                keypair.copy_from_slice(bytes);
+                SigningKey::from_keypair_bytes(&keypair)
+                    .map_err(|e| TransformError::Signing(format!("invalid keypair: {}", e)))?
+            }
+            n => {
+                return Err(TransformError::Signing(format!(
+                    "expected 32 or 64 key bytes, got {}",
+                    n
+                )));
+            }
+        };
+        Ok(Self::from_key(key))
+    }
+
+    fn from_key(key: SigningKey) -> Self {
+        Self {
+            key,
+            certificate_chain: Vec::new(),
+        }
+    }
+
+    /// Attach the certificate chain (leaf first) recorded with each signature.
+    pub fn with_certificate_chain(mut self, chain: Vec<Vec<u8>>) -> Self {
+        self.certificate_chain = chain;
+        self
+    }
+
+    /// The verifier matching this signer's public key.
+    pub fn verifier(&self) -> Ed25519Verifier {
+        Ed25519Verifier {
+            key: self.key.verifying_key(),
+        }
+    }
+}
+
+impl Signer for Ed25519Signer {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, TransformError> {
+        use ed25519_dalek::Signer as _;
+        Ok(self.key.sign(data).to_bytes().to_vec())
+    }
+
+    fn certificate_chain(&self) -> &[Vec<u8>] {
+        &self.certificate_chain
+    }
+
+    fn algorithm(&self) -> SigningAlg {
+        SigningAlg::Ed25519
+    }
+
+    fn key_id(&self) -> Vec<u8> {
+        key_id_for(self.key.verifying_key().as_bytes())
+    }
+}
+
+/// Ed25519 public key used to check signatures.
+#[derive(Debug, Clone)]
+pub struct Ed25519Verifier {
+    key: VerifyingKey,
+}
+
+impl Ed25519Verifier {
+    /// Load a public key from its raw 32-byte encoding.
+    pub fn from_public_key_bytes(bytes: &[u8]) -> Result<Self, TransformError> {
+        let key = VerifyingKey::try_from(bytes)
+            .map_err(|e| TransformError::Verification(format!("invalid public key: {}", e)))?;
+        Ok(Self { key })
+    }
+
+    /// Load a public key from a DER-encoded SubjectPublicKeyInfo.
+    pub fn from_public_key_der(der: &[u8]) -> Result<Self, TransformError> {
+        let key = VerifyingKey::from_public_key_der(der)
+            .map_err(|e| TransformError::Verification(format!("invalid public key: {}", e)))?;
+        Ok(Self { key })
+    }
+
+    /// Raw 32-byte public key.
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        self.key.to_bytes()
+    }
+}
+
+impl SignatureVerifier for Ed25519Verifier {
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<(), TransformError> {
+        let signature = Signature::from_slice(signature)
+            .map_err(|_| TransformError::Verification("malformed signature".into()))?;
+        self.key
+            .verify_strict(data, &signature)
+            .map_err(|_| TransformError::Verification("signature mismatch".into()))
+    }
+
+    fn algorithm(&self) -> SigningAlg {
+        SigningAlg::Ed25519
+    }
+
+    fn key_id(&self) -> Vec<u8> {
+        key_id_for(self.key.as_bytes())
+    }
+}
+
+// ============================================================================
+// ECDSA P-256 (ES256)
+// ============================================================================
+
+/// ECDSA signer on the P-256 curve with SHA-256 (`ES256`).
+///
+/// Signatures are deterministic (RFC 6979).
+pub struct P256Signer {
+    key: p256::ecdsa::SigningKey,
+    certificate_chain: Vec<Vec<u8>>,
+}
+
+impl P256Signer {
+    /// Load a private key from a PKCS#8 DER document.
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self, TransformError> {
+        let key = p256::ecdsa::SigningKey::from_pkcs8_der(der)
+            .map_err(|e| TransformError::Signing(format!("invalid PKCS#8 key: {}", e)))?;
+        Ok(Self::from_key(key))
+    }
+
+    /// Load a private key from a PKCS#8 PEM or DER file.
+    pub fn from_pkcs8_file(path: impl AsRef<Path>) -> Result<Self, TransformError> {
+        Ok(Self::from_key(read_pkcs8_file(path.as_ref())?))
+    }
+
+    /// Load a private key from its 32-byte scalar.
+    pub fn from_raw(bytes: &[u8]) -> Result<Self, TransformError> {
+        let key = p256::ecdsa::SigningKey::from_slice(bytes)
+            .map_err(|e| TransformError::Signing(format!("invalid P-256 key: {}", e)))?;
+        Ok(Self::from_key(key))
+    }
+
+    fn from_key(key: p256::ecdsa::SigningKey) -> Self {
+        Self {
+            key,
+            certificate_chain: Vec::new(),
+        }
+    }
+
+    /// Attach the certificate chain (leaf first) recorded with each signature.
+    pub fn with_certificate_chain(mut self, chain: Vec<Vec<u8>>) -> Self {
+        self.certificate_chain = chain;
+        self
+    }
+
+    /// The verifier matching this signer's public key.
+    pub fn verifier(&self) -> P256Verifier {
+        P256Verifier {
+            key: *self.key.verifying_key(),
+        }
+    }
+}
+
+impl Signer for P256Signer {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, TransformError> {
+        use p256::ecdsa::signature::Signer as _;
+        let signature: p256::ecdsa::Signature = self.key.sign(data);
+        Ok(signature.to_bytes().to_vec())
+    }
+
+    fn certificate_chain(&self) -> &[Vec<u8>] {
+        &self.certificate_chain
+    }
+
+    fn algorithm(&self) -> SigningAlg {
+        SigningAlg::Es256
+    }
+
+    fn key_id(&self) -> Vec<u8> {
+        key_id_for(&self.verifier().public_key_bytes())
+    }
+}
+
+/// P-256 public key used to check `ES256` signatures.
+#[derive(Debug, Clone)]
+pub struct P256Verifier {
+    key: p256::ecdsa::VerifyingKey,
+}
+
+impl P256Verifier {
+    /// Load a public key from its SEC1 encoding (compressed or not).
+    pub fn from_public_key_bytes(bytes: &[u8]) -> Result<Self, TransformError> {
+        let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(bytes)
+            .map_err(|e| TransformError::Verification(format!("invalid public key: {}", e)))?;
+        Ok(Self { key })
+    }
+
+    /// Load a public key from a DER-encoded SubjectPublicKeyInfo.
+    pub fn from_public_key_der(der: &[u8]) -> Result<Self, TransformError> {
+        let key = p256::ecdsa::VerifyingKey::from_public_key_der(der)
+            .map_err(|e| TransformError::Verification(format!("invalid public key: {}", e)))?;
+        Ok(Self { key })
+    }
+
+    /// Uncompressed SEC1 public key.
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        self.key.to_encoded_point(false).as_bytes().to_vec()
+    }
+}
+
+impl SignatureVerifier for P256Verifier {
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<(), TransformError> {
+        let signature = p256::ecdsa::Signature::from_slice(signature)
+            .map_err(|_| TransformError::Verification("malformed signature".into()))?;
+        self.key
+            .verify(data, &signature)
+            .map_err(|_| TransformError::Verification("signature mismatch".into()))
+    }
+
+    fn algorithm(&self) -> SigningAlg {
+        SigningAlg::Es256
+    }
+
+    fn key_id(&self) -> Vec<u8> {
+        key_id_for(&self.public_key_bytes())
+    }
+}
+
+// ============================================================================
+// RSA-PSS (PS256)
+// ============================================================================
+
+/// Smallest RSA modulus accepted, in bits.
+const MIN_RSA_BITS: usize = 2048;
+
+fn check_rsa_size(key: &RsaPublicKey) -> Result<(), String> {
+    let bits = key.n().bits();
+    if bits < MIN_RSA_BITS {
+        return Err(format!("{}-bit RSA key is below the {}-bit minimum", bits, MIN_RSA_BITS));
+    }
+    Ok(())
+}
+
+/// RSASSA-PSS signer with SHA-256 and a 32-byte salt (`PS256`).
+pub struct RsaPssSigner {
+    key: rsa::pss::SigningKey<Sha256>,
+    certificate_chain: Vec<Vec<u8>>,
+}
+
+impl RsaPssSigner {
+    /// Load a private key from a PKCS#8 DER document.
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self, TransformError> {
+        let key = RsaPrivateKey::from_pkcs8_der(der)
+            .map_err(|e| TransformError::Signing(format!("invalid PKCS#8 key: {}", e)))?;
+        Self::from_key(key)
+    }
+
+    /// Load a private key from a PKCS#8 PEM or DER file.
+    pub fn from_pkcs8_file(path: impl AsRef<Path>) -> Result<Self, TransformError> {
+        Self::from_key(read_pkcs8_file(path.as_ref())?)
+    }
+
+    fn from_key(key: RsaPrivateKey) -> Result<Self, TransformError> {
+        check_rsa_size(&key.to_public_key()).map_err(TransformError::Signing)?;
+        Ok(Self {
+            key: rsa::pss::SigningKey::new(key),
+            certificate_chain: Vec::new(),
+        })
+    }
+
+    /// Attach the certificate chain (leaf first) recorded with each signature.
+    pub fn with_certificate_chain(mut self, chain: Vec<Vec<u8>>) -> Self {
+        self.certificate_chain = chain;
+        self
+    }
+
+    /// The verifier matching this signer's public key.
+    pub fn verifier(&self) -> RsaPssVerifier {
+        RsaPssVerifier {
+            key: self.key.as_ref().to_public_key(),
+        }
+    }
+}
+
+impl Signer for RsaPssSigner {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, TransformError> {
+        let signature = self
+            .key
+            .try_sign_with_rng(&mut rsa::rand_core::OsRng, data)
+            .map_err(|e| TransformError::Signing(format!("RSA-PSS signing failed: {}", e)))?;
+        Ok(signature.to_vec())
+    }
+
+    fn certificate_chain(&self) -> &[Vec<u8>] {
+        &self.certificate_chain
+    }
+
+    fn algorithm(&self) -> SigningAlg {
+        SigningAlg::Ps256
+    }
+
+    fn key_id(&self) -> Vec<u8> {
+        key_id_for(&self.verifier().public_key_bytes())
+    }
+}
+
+/// RSA public key used to check `PS256` signatures.
+#[derive(Debug, Clone)]
+pub struct RsaPssVerifier {
+    key: RsaPublicKey,
+}
+
+impl RsaPssVerifier {
+    /// Load a public key from its PKCS#1 `RSAPublicKey` DER encoding.
+    pub fn from_public_key_bytes(bytes: &[u8]) -> Result<Self, TransformError> {
+        let key = RsaPublicKey::from_pkcs1_der(bytes)
+            .map_err(|e| TransformError::Verification(format!("invalid public key: {}", e)))?;
+        Self::from_key(key)
+    }
+
+    /// Load a public key from a DER-encoded SubjectPublicKeyInfo.
+    pub fn from_public_key_der(der: &[u8]) -> Result<Self, TransformError> {
+        let key = RsaPublicKey::from_public_key_der(der)
+            .map_err(|e| TransformError::Verification(format!("invalid public key: {}", e)))?;
+        Self::from_key(key)
+    }
+
+    fn from_key(key: RsaPublicKey) -> Result<Self, TransformError> {
+        check_rsa_size(&key).map_err(TransformError::Verification)?;
+        Ok(Self { key })
+    }
+
+    /// PKCS#1 `RSAPublicKey` DER encoding.
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        self.key
+            .to_pkcs1_der()
+            .expect("RSA public keys always encode")
+            .into_vec()
+    }
+}
+
+impl SignatureVerifier for RsaPssVerifier {
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<(), TransformError> {
+        let signature = rsa::pss::Signature::try_from(signature)
+            .map_err(|_| TransformError::Verification("malformed signature".into()))?;
+        rsa::pss::VerifyingKey::<Sha256>::new(self.key.clone())
+            .verify(data, &signature)
+            .map_err(|_| TransformError::Verification("signature mismatch".into()))
+    }
+
+    fn algorithm(&self) -> SigningAlg {
+        SigningAlg::Ps256
+    }
+
+    fn key_id(&self) -> Vec<u8> {
+        key_id_for(&self.public_key_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::pkcs8::{EncodePrivateKey, EncodePublicKey};
+
+    #[test]
+    fn test_sign_and_verify_roundtrip() {
+        let signer = Ed25519Signer::from_raw(&[1u8; 32]).unwrap();
+        let signature = signer.sign(b"claim").unwrap();
+
+        let verifier = signer.verifier();
+        assert!(verifier.verify(b"claim", &signature).is_ok());
+        assert!(verifier.verify(b"forged", &signature).is_err());
+    }
+
+    #[test]
+    fn test_load_from_pkcs8_and_spki() {
+        let key = SigningKey::from_bytes(&[2u8; 32]);
+        let pkcs8 = key.to_pkcs8_der().unwrap();
+        let spki = key.verifying_key().to_public_key_der().unwrap();
+
+        let signer = Ed25519Signer::from_pkcs8_der(pkcs8.as_bytes()).unwrap();
+        let verifier = Ed25519Verifier::from_public_key_der(spki.as_bytes()).unwrap();
+
+        let signature = signer.sign(b"claim").unwrap();
+        assert!(verifier.verify(b"claim", &signature).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_bad_key_material() {
+        assert!(Ed25519Signer::from_raw(&[0u8; 16]).is_err());
+        assert!(Ed25519Signer::from_pkcs8_der(b"not a key").is_err());
+        assert!(P256Signer::from_raw(&[0u8; 32]).is_err());
+        assert!(RsaPssSigner::from_pkcs8_file(testdata("rsa_1024.pem")).is_err());
+        assert!(RsaPssSigner::from_pkcs8_file(testdata("missing.pem")).is_err());
+    }
+
+    fn testdata(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join(name)
+    }
+
+    /// Sign with `signer`, then verify through [`verifier_for`] as a
+    /// certificate's public key would be.
+    fn check_roundtrip(signer: &dyn Signer, public_key: &[u8]) {
+        let signature = signer.sign(b"claim").unwrap();
+        let verifier = verifier_for(signer.algorithm(), public_key).unwrap();
+        assert_eq!(verifier.key_id(), signer.key_id());
+        assert!(verifier.verify(b"claim", &signature).is_ok());
+        assert!(verifier.verify(b"forged", &signature).is_err());
+    }
+
+    #[test]
+    fn test_p256_from_pem_file() {
+        let signer = P256Signer::from_pkcs8_file(testdata("p256.pem")).unwrap();
+        assert_eq!(signer.algorithm(), SigningAlg::Es256);
+        assert_eq!(signer.sign(b"claim").unwrap().len(), 64);
+        check_roundtrip(&signer, &signer.verifier().public_key_bytes());
+    }
+
+    #[test]
+    fn test_rsa_pss_from_pem_and_der_files() {
+        let signer = RsaPssSigner::from_pkcs8_file(testdata("rsa_2048.pem")).unwrap();
+        assert_eq!(signer.algorithm(), SigningAlg::Ps256);
+        check_roundtrip(&signer, &signer.verifier().public_key_bytes());
+
+        let der = pkcs8::SecretDocument::read_pem_file(testdata("rsa_2048.pem")).unwrap().1;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key.der");
+        std::fs::write(&path, der.as_bytes()).unwrap();
+        let from_der = RsaPssSigner::from_pkcs8_file(&path).unwrap();
+        assert_eq!(from_der.key_id(), signer.key_id());
+    }
+
+    #[test]
+    fn test_verifier_rejects_other_algorithms() {
+        let p256 = P256Signer::from_pkcs8_file(testdata("p256.pem")).unwrap();
+        let rsa = RsaPssSigner::from_pkcs8_file(testdata("rsa_2048.pem")).unwrap();
+        let signature = p256.sign(b"claim").unwrap();
+        assert!(rsa.verifier().verify(b"claim", &signature).is_err());
+        assert!(verifier_for(SigningAlg::Ps256, &p256.verifier().public_key_bytes()).is_err());
+    }
+}
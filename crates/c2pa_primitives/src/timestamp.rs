@@ -0,0 +1,207 @@
+//! Trusted timestamps over claim signatures.
+//!
+//! A [`TimestampAuthority`] countersigns the claim signature together with
+//! the current time, producing a [`TimestampToken`] that is stored next to
+//! the signature. Because the token covers the signature bytes, a claim
+//! cannot be re-signed later and passed off as older than it is.
+//!
+//! The token's signed content is a deterministic CBOR map:
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   "time":    uint,   ; seconds since the Unix epoch
+//!   "imprint": bstr,   ; SHA-256 of the claim signature
+//!   "tsa":     bstr,   ; key identifier of the authority
+//! }
+//! ```
+//!
+//! [`LocalTimestampAuthority`] is an in-process authority with its own key,
+//! standing in for an RFC 3161 service so the whole flow runs offline.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+use crate::cbor::Value;
+use crate::claim::{self, bytes, hash32, text};
+use crate::{Ed25519Signer, Ed25519Verifier, Signer, SigningAlg, TransformError};
+
+/// Version of the signed token content.
+const TIMESTAMP_FORMAT_VERSION: u64 = 1;
+
+/// Issues signed timestamps.
+pub trait TimestampAuthority {
+    /// Timestamp `message` (a claim signature) at the current time.
+    fn timestamp(&self, message: &[u8]) -> Result<TimestampToken, TransformError>;
+}
+
+/// A timestamp authority's signed statement that a claim signature
+/// existed at `time`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimestampToken {
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    /// SHA-256 of the timestamped claim signature.
+    pub message_imprint: [u8; 32],
+    /// Identifier of the authority's key.
+    pub tsa_key_id: Vec<u8>,
+    /// Algorithm of `signature`.
+    pub algorithm: SigningAlg,
+    /// Authority's signature over the token content.
+    pub signature: Vec<u8>,
+}
+
+impl TimestampToken {
+    /// Imprint of a message as recorded in a token.
+    pub fn imprint(message: &[u8]) -> [u8; 32] {
+        Sha256::digest(message).into()
+    }
+
+    /// The bytes the authority signs.
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        Value::map([
+            ("version", Value::uint(TIMESTAMP_FORMAT_VERSION)),
+            ("time", Value::uint(self.time)),
+            ("imprint", Value::bytes(self.message_imprint)),
+            ("tsa", Value::bytes(self.tsa_key_id.clone())),
+        ])
+        .to_bytes()
+    }
+
+    /// True if this token was issued for `message`.
+    pub fn covers(&self, message: &[u8]) -> bool {
+        self.message_imprint == Self::imprint(message)
+    }
+}
+
+/// In-process timestamp authority signing with its own Ed25519 key.
+///
+/// # Example
+///
+/// ```
+/// use c2pa_primitives::*;
+///
+/// let tsa = LocalTimestampAuthority::new(Ed25519Signer::from_raw(&[9u8; 32]).unwrap());
+/// let signer = Ed25519Signer::from_raw(&[1u8; 32]).unwrap();
+/// let trusted = TrustedKeys::new()
+///     .add_key(signer.verifier())
+///     .add_timestamp_authority(tsa.verifier())
+///     .require_timestamp(true);
+///
+/// let value = C2paBuilder::new(42u32)
+///     .timestamp_authority(tsa)
+///     .require_timestamp(true)
+///     .sign(&signer)
+///     .unwrap();
+/// let unverified = C2pa::new(*value.payload(), value.provenance().clone());
+/// assert!(verify(unverified, &trusted).is_ok());
+/// ```
+pub struct LocalTimestampAuthority {
+    signer: Ed25519Signer,
+    /// Fixed time to issue tokens at, instead of the system clock.
+    fixed_time: Option<u64>,
+}
+
+impl LocalTimestampAuthority {
+    pub fn new(signer: Ed25519Signer) -> Self {
+        Self {
+            signer,
+            fixed_time: None,
+        }
+    }
+
+    /// Issue every token at `time` (seconds since the Unix epoch).
+    pub fn with_fixed_time(mut self, time: u64) -> Self {
+        self.fixed_time = Some(time);
+        self
+    }
+
+    /// The verifier to trust for this authority's tokens.
+    pub fn verifier(&self) -> Ed25519Verifier {
+        self.signer.verifier()
+    }
+
+    fn now(&self) -> Result<u64, TransformError> {
+        match self.fixed_time {
+            Some(time) => Ok(time),
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .map_err(|e| TransformError::Signing(format!("system clock error: {}", e))),
+        }
+    }
+}
+
+impl TimestampAuthority for LocalTimestampAuthority {
+    fn timestamp(&self, message: &[u8]) -> Result<TimestampToken, TransformError> {
+        let mut token = TimestampToken {
+            time: self.now()?,
+            message_imprint: TimestampToken::imprint(message),
+            tsa_key_id: self.signer.key_id(),
+            algorithm: self.signer.algorithm(),
+            signature: Vec::new(),
+        };
+        token.signature = self.signer.sign(&token.signed_bytes())?;
+        Ok(token)
+    }
+}
+
+// Manifest encoding, used by the `manifest` module.
+
+pub(crate) fn token_value(token: &TimestampToken) -> Value {
+    Value::map([
+        ("time", Value::uint(token.time)),
+        ("imprint", Value::bytes(token.message_imprint)),
+        ("tsa", Value::bytes(token.tsa_key_id.clone())),
+        ("alg", Value::text(token.algorithm.as_str())),
+        ("signature", Value::bytes(token.signature.clone())),
+    ])
+}
+
+pub(crate) fn decode_token(value: &Value) -> Result<TimestampToken, TransformError> {
+    Ok(TimestampToken {
+        time: claim::uint(value, "time")?,
+        message_imprint: hash32(value, "imprint")?,
+        tsa_key_id: bytes(value, "tsa")?.to_vec(),
+        algorithm: text(value, "alg")?.parse()?,
+        signature: bytes(value, "signature")?.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_signer;
+    use crate::SignatureVerifier;
+
+    fn tsa() -> LocalTimestampAuthority {
+        LocalTimestampAuthority::new(test_signer(9))
+            .with_fixed_time(1_700_000_000)
+    }
+
+    #[test]
+    fn test_token_covers_message_and_verifies() {
+        let tsa = tsa();
+        let token = tsa.timestamp(b"signature").unwrap();
+        assert_eq!(token.time, 1_700_000_000);
+        assert!(token.covers(b"signature"));
+        assert!(!token.covers(b"other"));
+        assert!(tsa.verifier().verify(&token.signed_bytes(), &token.signature).is_ok());
+    }
+
+    #[test]
+    fn test_backdated_token_fails() {
+        let tsa = tsa();
+        let mut token = tsa.timestamp(b"signature").unwrap();
+        token.time -= 3600;
+        assert!(tsa.verifier().verify(&token.signed_bytes(), &token.signature).is_err());
+    }
+
+    #[test]
+    fn test_token_encoding_roundtrip() {
+        let token = tsa().timestamp(b"signature").unwrap();
+        let decoded = decode_token(&Value::from_bytes(&token_value(&token).to_bytes()).unwrap());
+        assert_eq!(decoded.unwrap(), token);
+    }
+}
@@ -0,0 +1,256 @@
+//! Structured verification results.
+//!
+//! Every check produces [`ValidationStatus`] entries with a machine-readable
+//! [`StatusCode`], grouped per manifest in a [`ValidationReport`].
+
+use std::fmt;
+
+use crate::{ClaimHash, Provenance};
+
+/// Machine-readable failure classes.
+///
+/// The string form (see [`StatusCode::as_str`]) follows the C2PA
+/// `category.detail` naming so it can be matched on in CI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusCode {
+    /// The manifest to verify was not supplied.
+    ManifestMissing,
+    /// The recomputed claim hash differs from the stored one.
+    ClaimHashMismatch,
+    /// The manifest carries no claim signature.
+    SignatureMissing,
+    /// The claim signature does not verify.
+    SignatureInvalid,
+    /// The signing key is not in the trusted set.
+    UntrustedSigner,
+    /// The payload does not match the asset binding.
+    BindingMismatch,
+    /// An ingredient references a manifest that was not supplied.
+    IngredientMissing,
+    /// An ingredient's recorded binding differs from its manifest's binding.
+    IngredientBindingMismatch,
+    /// An ingredient leads back to a manifest that depends on it.
+    IngredientCycle,
+    /// A timestamp is required but the claim signature has none.
+    TimestampMissing,
+    /// The timestamp does not cover the claim signature or does not verify.
+    TimestampMismatch,
+    /// The timestamp authority is not in the trusted set.
+    TimestampUntrusted,
+    /// The certificate chain is malformed, broken or violates its constraints.
+    CertificateInvalid,
+    /// A certificate in the chain is outside its validity period.
+    CertificateExpired,
+}
+
+impl StatusCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ManifestMissing => "manifest.missing",
+            Self::ClaimHashMismatch => "claim.hash.mismatch",
+            Self::SignatureMissing => "claimSignature.missing",
+            Self::SignatureInvalid => "claimSignature.mismatch",
+            Self::UntrustedSigner => "signingCredential.untrusted",
+            Self::BindingMismatch => "assertion.dataHash.mismatch",
+            Self::IngredientMissing => "ingredient.manifest.missing",
+            Self::IngredientBindingMismatch => "ingredient.binding.mismatch",
+            Self::IngredientCycle => "ingredient.cycle",
+            Self::TimestampMissing => "timeStamp.missing",
+            Self::TimestampMismatch => "timeStamp.mismatch",
+            Self::TimestampUntrusted => "timeStamp.untrusted",
+            Self::CertificateInvalid => "signingCredential.invalid",
+            Self::CertificateExpired => "signingCredential.expired",
+        }
+    }
+}
+
+impl fmt::Display for StatusCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How far a manifest can be relied on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trust {
+    /// Every check passed and the signer is trusted.
+    Trusted,
+    /// Every check passed except that the signer is not trusted: the
+    /// manifest is intact, but nobody vouches for who made it.
+    Untrusted,
+    /// Some other check failed.
+    Invalid,
+}
+
+/// One failed check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationStatus {
+    pub code: StatusCode,
+    /// Human-readable detail.
+    pub explanation: String,
+    /// Claim hash of the ingredient involved, for ingredient failures.
+    pub ingredient: Option<ClaimHash>,
+}
+
+impl ValidationStatus {
+    pub fn new(code: StatusCode, explanation: impl Into<String>) -> Self {
+        Self {
+            code,
+            explanation: explanation.into(),
+            ingredient: None,
+        }
+    }
+
+    pub(crate) fn for_ingredient(code: StatusCode, ingredient: &ClaimHash) -> Self {
+        Self {
+            code,
+            explanation: format!("ingredient {}", crate::debug::hash_short(ingredient.as_bytes())),
+            ingredient: Some(ingredient.clone()),
+        }
+    }
+}
+
+/// Validation results for one manifest.
+#[derive(Debug, Clone)]
+pub struct ManifestStatus {
+    /// `None` if the manifest was not available.
+    pub manifest_id: Option<String>,
+    pub claim_hash: ClaimHash,
+    /// Failed checks; empty if the manifest is valid.
+    pub statuses: Vec<ValidationStatus>,
+}
+
+impl ManifestStatus {
+    pub fn is_valid(&self) -> bool {
+        self.statuses.is_empty()
+    }
+
+    pub fn has(&self, code: StatusCode) -> bool {
+        self.statuses.iter().any(|status| status.code == code)
+    }
+
+    pub fn trust(&self) -> Trust {
+        if self
+            .statuses
+            .iter()
+            .any(|status| status.code != StatusCode::UntrustedSigner)
+        {
+            Trust::Invalid
+        } else if self.statuses.is_empty() {
+            Trust::Trusted
+        } else {
+            Trust::Untrusted
+        }
+    }
+}
+
+/// Validation results for a manifest or a whole provenance graph.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    /// One entry per manifest checked, head first.
+    pub manifests: Vec<ManifestStatus>,
+}
+
+impl ValidationReport {
+    /// Report for a single manifest.
+    pub(crate) fn for_manifest(provenance: &Provenance, statuses: Vec<ValidationStatus>) -> Self {
+        Self {
+            manifests: vec![ManifestStatus {
+                manifest_id: Some(provenance.manifest_id.clone()),
+                claim_hash: provenance.claim_hash.clone(),
+                statuses,
+            }],
+        }
+    }
+
+    /// True if no manifest reported a failure.
+    pub fn is_valid(&self) -> bool {
+        self.manifests.iter().all(ManifestStatus::is_valid)
+    }
+
+    /// The weakest trust level of any manifest.
+    pub fn trust(&self) -> Trust {
+        let levels = self.manifests.iter().map(ManifestStatus::trust);
+        let mut trust = Trust::Trusted;
+        for level in levels {
+            match level {
+                Trust::Invalid => return Trust::Invalid,
+                Trust::Untrusted => trust = Trust::Untrusted,
+                Trust::Trusted => {}
+            }
+        }
+        trust
+    }
+
+    /// True if any manifest reported `code`.
+    pub fn has(&self, code: StatusCode) -> bool {
+        self.manifests.iter().any(|manifest| manifest.has(code))
+    }
+
+    /// Results for the manifest with the given claim hash.
+    pub fn manifest(&self, claim_hash: &ClaimHash) -> Option<&ManifestStatus> {
+        self.manifests
+            .iter()
+            .find(|manifest| &manifest.claim_hash == claim_hash)
+    }
+
+    /// All failures, paired with the manifest they were found on.
+    pub fn failures(&self) -> impl Iterator<Item = (&ManifestStatus, &ValidationStatus)> {
+        self.manifests.iter().flat_map(|manifest| {
+            manifest
+                .statuses
+                .iter()
+                .map(move |status| (manifest, status))
+        })
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (manifest, status) in self.failures() {
+            if !first {
+                f.write_str("; ")?;
+            }
+            first = false;
+            let id = manifest.manifest_id.as_deref().unwrap_or("<missing>");
+            write!(f, "{}: {} ({})", id, status.code, status.explanation)?;
+        }
+        if first {
+            f.write_str("valid")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_groups_failures_by_manifest() {
+        let ok = ManifestStatus {
+            manifest_id: Some("urn:uuid:ok".into()),
+            claim_hash: ClaimHash::from_bytes([1; 32]),
+            statuses: vec![],
+        };
+        let bad = ManifestStatus {
+            manifest_id: Some("urn:uuid:bad".into()),
+            claim_hash: ClaimHash::from_bytes([2; 32]),
+            statuses: vec![ValidationStatus::new(StatusCode::SignatureInvalid, "bad signature")],
+        };
+        let report = ValidationReport {
+            manifests: vec![ok, bad],
+        };
+
+        assert!(!report.is_valid());
+        assert!(report.has(StatusCode::SignatureInvalid));
+        assert!(!report.has(StatusCode::BindingMismatch));
+        assert!(report.manifest(&ClaimHash::from_bytes([1; 32])).unwrap().is_valid());
+        assert_eq!(
+            report.to_string(),
+            "urn:uuid:bad: claimSignature.mismatch (bad signature)"
+        );
+        assert_eq!(ValidationReport::default().to_string(), "valid");
+    }
+}
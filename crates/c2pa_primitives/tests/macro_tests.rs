@@ -67,7 +67,7 @@ fn test_macro_chain() {
 // Phase 1: Transform with parameter commits
 // ============================================================================
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, C2paCommit)]
 struct Offset {
     dx: i32,
     dy: i32,
@@ -119,11 +119,41 @@ fn test_macro_different_params_produce_different_results() {
     });
 }
 
+#[test]
+fn test_param_commit_is_salted_and_openable() {
+    with_new_ctx("test", || {
+        let source: C2pa<i32, Verified> = C2paBuilder::new(100i32).sign(&TestSigner).unwrap();
+
+        let first = shift_value_c2pa(&source, Offset { dx: 1, dy: 2 }).unwrap();
+        let second = shift_value_c2pa(&source, Offset { dx: 1, dy: 2 }).unwrap();
+
+        // Same value, different salt: commitments do not repeat
+        let commit = |value: &C2pa<i32, Verified>| recorded_commitments(value.provenance());
+        assert_eq!(commit(&first).len(), 1);
+        assert_ne!(commit(&first), commit(&second));
+
+        // The producer keeps the openings and can reveal them to an auditor
+        let openings = with_ctx(|ctx| ctx.take_param_openings());
+        assert_eq!(openings.len(), 2);
+        let (claim_hash, opening) = &openings[0];
+        assert_eq!(claim_hash, &first.provenance().claim_hash);
+        assert_eq!(opening.name, "offset");
+        assert!(opening.opens(first.provenance()));
+        assert!(!opening.opens(second.provenance()));
+
+        let guessed = ParamOpening {
+            value: Offset { dx: 0, dy: 3 }.commit_bytes(),
+            ..opening.clone()
+        };
+        assert!(!guessed.opens(first.provenance()));
+    });
+}
+
 // ============================================================================
 // Multiple parameters test
 // ============================================================================
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, C2paCommit)]
 struct Scale {
     factor: f64,
 }
@@ -203,6 +233,144 @@ fn test_different_relationships() {
     });
 }
 
+// ============================================================================
+// Multi-input transform tests
+// ============================================================================
+
+#[c2pa_transform(name = "merge", relationship = "composedFrom", inputs(a, b))]
+fn merge(a: &Vec<u8>, b: &Vec<u8>) -> Vec<u8> {
+    [a.as_slice(), b.as_slice()].concat()
+}
+
+#[c2pa_transform(name = "weighted_sum", inputs(x, y), record(params(weight)))]
+fn weighted_sum(x: &u32, weight: u32, y: &u32) -> u32 {
+    x * weight + y
+}
+
+#[test]
+fn test_multi_input_records_every_ingredient() {
+    with_new_ctx("test", || {
+        let a = C2paBuilder::new(vec![1u8, 2]).sign(&TestSigner).unwrap();
+        let b = C2paBuilder::new(vec![3u8]).sign(&TestSigner).unwrap();
+
+        let merged = merge_c2pa(&a, &b).unwrap();
+        assert_eq!(merged.payload(), &vec![1, 2, 3]);
+
+        let ingredients = &merged.provenance().ingredients;
+        assert_eq!(ingredients.len(), 2);
+        assert_eq!(ingredients[0].claim_hash, a.provenance().claim_hash);
+        assert_eq!(ingredients[1].claim_hash, b.provenance().claim_hash);
+        assert!(ingredients
+            .iter()
+            .all(|i| i.relationship == IngredientRelation::ComposedFrom));
+    });
+}
+
+#[test]
+fn test_multi_input_keeps_argument_order() {
+    with_new_ctx("test", || {
+        let x = C2paBuilder::new(3u32).sign(&TestSigner).unwrap();
+        let y = C2paBuilder::new(4u32).sign(&TestSigner).unwrap();
+
+        let result = weighted_sum_c2pa(&x, 10, &y).unwrap();
+        assert_eq!(*result.payload(), 34);
+        assert_eq!(result.provenance().ingredients.len(), 2);
+        assert_eq!(
+            result.provenance().ingredients[1].claim_hash,
+            y.provenance().claim_hash
+        );
+    });
+}
+
+// Argument names that the generated wrapper must not shadow
+#[c2pa_transform(name = "offset_by", record(params(ingredients)))]
+fn offset_by(x: &u32, ingredients: u32, out_payload: u32) -> u32 {
+    x + ingredients * out_payload
+}
+
+#[test]
+fn test_wrapper_keeps_argument_names_intact() {
+    with_new_ctx("test", || {
+        let x = C2paBuilder::new(1u32).sign(&TestSigner).unwrap();
+        let result = offset_by_c2pa(&x, 2, 3).unwrap();
+        assert_eq!(*result.payload(), 7);
+        assert_eq!(result.provenance().ingredients.len(), 1);
+        assert_eq!(recorded_commitments(result.provenance())[0].0, "ingredients");
+    });
+}
+
+// ============================================================================
+// c2pa_composite tests
+// ============================================================================
+
+#[c2pa_composite(name = "hconcat")]
+fn hconcat(a: &Image, b: &Image) -> Result<Image, String> {
+    if a.height != b.height {
+        return Err("height mismatch".into());
+    }
+    let mut pixels = Vec::with_capacity(a.pixels.len() + b.pixels.len());
+    for y in 0..a.height as usize {
+        pixels.extend_from_slice(&a.pixels[y * a.width as usize..][..a.width as usize]);
+        pixels.extend_from_slice(&b.pixels[y * b.width as usize..][..b.width as usize]);
+    }
+    Ok(Image {
+        width: a.width + b.width,
+        height: a.height,
+        pixels,
+    })
+}
+
+#[c2pa_composite(
+    name = "overlay",
+    composite = Overlay,
+    relationship(base = "parentOf", layer = "componentOf")
+)]
+fn overlay(base: &u32, layer: &u32) -> u32 {
+    base | layer
+}
+
+#[test]
+fn test_composite_records_both_inputs() {
+    with_new_ctx("test", || {
+        let a = C2paBuilder::new(Image::new(2, 2, 1)).sign(&TestSigner).unwrap();
+        let b = C2paBuilder::new(Image::new(3, 2, 2)).sign(&TestSigner).unwrap();
+
+        let joined = hconcat_c2pa(&a, &b).unwrap();
+        assert_eq!(joined.payload().width, 5);
+        assert_eq!(joined.payload().get(2, 1), Some(2));
+
+        let ingredients = &joined.provenance().ingredients;
+        assert_eq!(ingredients.len(), 2);
+        assert_eq!(ingredients[0].claim_hash, a.provenance().claim_hash);
+        assert_eq!(ingredients[1].relationship, IngredientRelation::ComposedFrom);
+    });
+}
+
+#[test]
+fn test_composite_propagates_errors() {
+    with_new_ctx("test", || {
+        let a = C2paBuilder::new(Image::new(2, 2, 1)).sign(&TestSigner).unwrap();
+        let b = C2paBuilder::new(Image::new(2, 3, 1)).sign(&TestSigner).unwrap();
+        assert!(hconcat_c2pa(&a, &b).is_err());
+    });
+}
+
+#[test]
+fn test_composite_impl_and_relationships() {
+    let base = C2paBuilder::new(0b01u32).sign(&TestSigner).unwrap();
+    let layer = C2paBuilder::new(0b10u32).sign(&TestSigner).unwrap();
+
+    // The generated struct is usable as a plain C2paComposite
+    let mut ctx = TransformContext::new("explicit");
+    let result = Overlay.compose(&base, &layer, &mut ctx).unwrap();
+    assert_eq!(*result.payload(), 0b11);
+    assert_eq!(ctx.transform_name.as_deref(), Some("overlay"));
+
+    let ingredients = &result.provenance().ingredients;
+    assert_eq!(ingredients[0].relationship, IngredientRelation::ParentOf);
+    assert_eq!(ingredients[1].relationship, IngredientRelation::ComponentOf);
+}
+
 // ============================================================================
 // c2pa_source tests
 // ============================================================================
@@ -233,6 +401,164 @@ fn test_source_and_transform_chain() {
     });
 }
 
+// ============================================================================
+// derive(C2paBindable) tests
+// ============================================================================
+
+#[derive(C2paBindable)]
+#[c2pa(media_type = "application/x-reading")]
+struct Reading {
+    sensor: String,
+    value: u32,
+    #[c2pa(skip)]
+    label: String,
+}
+
+/// Same fields, declared in a different order.
+#[derive(C2paBindable)]
+struct ReorderedReading {
+    value: u32,
+    sensor: String,
+}
+
+#[derive(C2paBindable)]
+struct Pair(u32, u32);
+
+fn reading(value: u32, label: &str) -> Reading {
+    Reading {
+        sensor: "t1".into(),
+        value,
+        label: label.into(),
+    }
+}
+
+#[test]
+fn test_derive_hashes_every_field() {
+    assert_ne!(reading(1, "a").content_hash(), reading(2, "a").content_hash());
+
+    let mut other_sensor = reading(1, "a");
+    other_sensor.sensor = "t2".into();
+    assert_ne!(reading(1, "a").content_hash(), other_sensor.content_hash());
+
+    assert_ne!(Pair(1, 2).content_hash(), Pair(2, 1).content_hash());
+}
+
+#[test]
+fn test_derive_skip_and_media_type() {
+    assert_ne!(reading(1, "a").label, reading(1, "b").label);
+    assert_eq!(reading(1, "a").content_hash(), reading(1, "b").content_hash());
+    assert_eq!(reading(1, "a").media_type(), "application/x-reading");
+    assert_eq!(Pair(1, 2).media_type(), "application/octet-stream");
+}
+
+#[test]
+fn test_derive_is_independent_of_field_order() {
+    let reordered = ReorderedReading {
+        value: 1,
+        sensor: "t1".into(),
+    };
+    assert_eq!(reading(1, "a").content_hash(), reordered.content_hash());
+}
+
+#[test]
+fn test_derived_type_verifies() {
+    let signer = Ed25519Signer::from_raw(&[3u8; 32]).unwrap();
+    let trusted = TrustedKeys::new().add_key(signer.verifier());
+    let signed = C2paBuilder::new(reading(5, "a")).sign(&signer).unwrap();
+
+    // The skipped label differs, but the binding still matches
+    let relabeled = C2pa::new(reading(5, "b"), signed.provenance().clone());
+    assert!(verify(relabeled, &trusted).is_ok());
+
+    let altered = C2pa::new(reading(6, "a"), signed.provenance().clone());
+    assert!(verify(altered, &trusted).is_err());
+}
+
+// ============================================================================
+// derive(C2paCommit) tests
+// ============================================================================
+
+/// `Offset` with its fields declared in a different order.
+#[derive(C2paCommit)]
+struct ReorderedOffset {
+    dy: i32,
+    dx: i32,
+}
+
+#[derive(Debug, C2paCommit)]
+enum Mode {
+    Fast,
+    Exact { tolerance: f64 },
+    Window(u32),
+}
+
+#[c2pa_transform(name = "tune", record(params(mode)))]
+fn tune(x: &u32, mode: Mode) -> u32 {
+    match mode {
+        Mode::Fast => *x,
+        Mode::Exact { .. } => x + 1,
+        Mode::Window(size) => x + size,
+    }
+}
+
+#[test]
+fn test_derive_commit_is_independent_of_field_order() {
+    let offset = Offset { dx: 1, dy: 2 };
+    assert_eq!(offset.commit_bytes(), ReorderedOffset { dy: 2, dx: 1 }.commit_bytes());
+    assert_ne!(offset.commit_bytes(), Offset { dx: 2, dy: 1 }.commit_bytes());
+}
+
+#[test]
+fn test_derive_commit_enum_variants() {
+    assert_ne!(Mode::Fast.commit_bytes(), Mode::Exact { tolerance: 0.0 }.commit_bytes());
+    assert_ne!(
+        Mode::Exact { tolerance: 0.1 }.commit_bytes(),
+        Mode::Exact { tolerance: 0.2 }.commit_bytes()
+    );
+    assert_ne!(Mode::Window(4).commit_bytes(), Mode::Window(5).commit_bytes());
+}
+
+/// Derives both; each skip applies only to its own derive.
+#[derive(C2paBindable, C2paCommit)]
+#[c2pa(media_type = "application/x-window")]
+struct Window {
+    size: u32,
+    #[c2pa(skip)]
+    cache_hint: u32,
+    #[c2pa_commit(skip)]
+    comment: String,
+}
+
+fn window(cache_hint: u32, comment: &str) -> Window {
+    Window {
+        size: 8,
+        cache_hint,
+        comment: comment.into(),
+    }
+}
+
+#[test]
+fn test_derive_bindable_and_commit_skip_independently() {
+    assert_eq!(window(1, "a").content_hash(), window(2, "a").content_hash());
+    assert_ne!(window(1, "a").content_hash(), window(1, "b").content_hash());
+
+    assert_eq!(window(1, "a").commit_bytes(), window(1, "b").commit_bytes());
+    assert_ne!(window(1, "a").commit_bytes(), window(2, "a").commit_bytes());
+}
+
+#[test]
+fn test_recorded_enum_param_opens() {
+    with_new_ctx("test", || {
+        let source = C2paBuilder::new(1u32).sign(&TestSigner).unwrap();
+        let result = tune_c2pa(&source, Mode::Window(4)).unwrap();
+        assert_eq!(*result.payload(), 5);
+
+        let (_, opening) = with_ctx(|ctx| ctx.take_param_openings()).remove(0);
+        assert_eq!(opening.value, Mode::Window(4).commit_bytes());
+        assert!(opening.opens(result.provenance()));
+    });
+}
+
 // ============================================================================
 // c2pa_pipeline tests
 // ============================================================================
@@ -250,6 +576,102 @@ fn test_pipeline_basic() {
     assert_eq!(result, 126);
 }
 
+#[c2pa_pipeline]
+fn inherit_generator() -> Result<C2pa<u32, Verified>, TransformError> {
+    let start = origin_value_c2pa()?;
+    triple_c2pa(&start)
+}
+
+#[c2pa_pipeline(generator = "inner_override")]
+fn override_generator() -> String {
+    with_ctx(|ctx| ctx.generator.clone())
+}
+
+#[c2pa_pipeline(generator = "outer")]
+fn run_nested() -> Result<(C2pa<u32, Verified>, String), TransformError> {
+    let depth = ctx_depth();
+    let inherited = inherit_generator()?;
+    let overridden = override_generator();
+    // The outer context is back in place after the sub-pipelines return
+    assert_eq!(ctx_depth(), depth);
+    assert_eq!(with_ctx(|ctx| ctx.generator.clone()), "outer");
+    Ok((inherited, overridden))
+}
+
+fn transform_assertion(value: &C2pa<u32, Verified>) -> String {
+    let assertion = value
+        .provenance()
+        .assertions
+        .iter()
+        .find(|a| a.label == "c2pa.transform")
+        .unwrap();
+    String::from_utf8(assertion.data.clone()).unwrap()
+}
+
+#[test]
+fn test_nested_pipelines_inherit_or_override_generator() {
+    let (inherited, overridden) = run_nested().unwrap();
+    assert_eq!(inherited.provenance().generator, "outer");
+    assert_eq!(overridden, "inner_override");
+    assert!(!has_ctx());
+}
+
+#[test]
+fn test_sub_pipeline_recorded_in_provenance() {
+    let (nested, _) = run_nested().unwrap();
+    assert!(transform_assertion(&nested).contains(r#""pipeline":["run_nested","inherit_generator"]"#));
+
+    let top_level = inherit_generator().unwrap();
+    assert_eq!(top_level.provenance().generator, "c2pa_pipeline");
+    assert!(transform_assertion(&top_level).contains(r#""pipeline":["inherit_generator"]"#));
+}
+
+#[c2pa_pipeline]
+fn shift_in_sub_pipeline() -> Result<C2pa<i32, Verified>, TransformError> {
+    let source = C2paBuilder::new(7i32).sign(&TestSigner)?;
+    shift_value_c2pa(&source, Offset { dx: 3, dy: 4 })
+}
+
+#[test]
+fn test_sub_pipeline_openings_reach_caller() {
+    // A sub-pipeline hands its openings to the enclosing pipeline
+    with_new_ctx("outer", || {
+        let shifted = shift_in_sub_pipeline().unwrap();
+        let openings = with_ctx(|ctx| ctx.take_param_openings());
+        assert_eq!(openings.len(), 1);
+        assert_eq!(openings[0].0, shifted.provenance().claim_hash);
+        assert!(openings[0].1.opens(shifted.provenance()));
+    });
+
+    // At the top level they go to the caller
+    let (shifted, openings) = collect_param_openings(|| shift_in_sub_pipeline().unwrap());
+    assert_eq!(openings.len(), 1);
+    assert!(openings[0].1.opens(shifted.provenance()));
+    assert!(collect_param_openings(|| ()).1.is_empty());
+}
+
+#[c2pa_pipeline(generator = "panicking")]
+fn panicking_pipeline() -> u32 {
+    panic!("pipeline failed")
+}
+
+#[test]
+fn test_context_restored_after_panic() {
+    let result = std::panic::catch_unwind(panicking_pipeline);
+    assert!(result.is_err());
+    assert!(!has_ctx());
+
+    // Nested: the outer context survives a panicking sub-pipeline
+    with_new_ctx("outer", || {
+        assert!(std::panic::catch_unwind(panicking_pipeline).is_err());
+        assert_eq!(ctx_depth(), 1);
+        assert_eq!(with_ctx(|ctx| ctx.generator.clone()), "outer");
+    });
+
+    // The thread is usable for the next pipeline
+    assert_eq!(run_pipeline().unwrap(), 126);
+}
+
 #[test]
 #[should_panic(expected = "with_ctx called outside #[c2pa_pipeline]")]
 fn test_transform_without_pipeline_panics() {
@@ -260,3 +682,278 @@ fn test_transform_without_pipeline_panics() {
         .unwrap();
     let _ = triple_c2pa(&source);
 }
+
+// ============================================================================
+// pipeline policy tests
+// ============================================================================
+
+#[c2pa_pipeline(generator = "policy")]
+fn run_with_policy() -> Result<Vec<Provenance>, TransformError> {
+    with_ctx(|ctx| {
+        ctx.assertions
+            .push(CustomAssertion::json("org.example.policy", r#"{"tier":1}"#))
+    });
+    let start = origin_value_c2pa()?;
+    let tripled = triple_c2pa(&start)?;
+    Ok(vec![start.provenance().clone(), tripled.provenance().clone()])
+}
+
+#[c2pa_pipeline(generator = "timestamped")]
+fn run_requiring_timestamp() -> Result<C2pa<u32, Verified>, TransformError> {
+    with_ctx(|ctx| ctx.require_timestamp = true);
+    origin_value_c2pa()
+}
+
+#[c2pa_pipeline(generator = "timestamped")]
+fn run_with_authority() -> Result<C2pa<u32, Verified>, TransformError> {
+    with_ctx(|ctx| {
+        *ctx = ctx
+            .clone()
+            .with_timestamp(true)
+            .with_timestamp_authority(LocalTimestampAuthority::new(timestamp_signer()));
+    });
+    let start = origin_value_c2pa()?;
+    triple_c2pa(&start)
+}
+
+fn timestamp_signer() -> Ed25519Signer {
+    Ed25519Signer::from_raw(&[23u8; 32]).unwrap()
+}
+
+#[test]
+fn test_pipeline_timestamps_every_signature() {
+    let result = run_with_authority().unwrap();
+    let token = result.provenance().signature.as_ref().unwrap().timestamp.as_ref().unwrap();
+    assert_eq!(token.tsa_key_id, timestamp_signer().key_id());
+    assert!(token.covers(&result.provenance().signature.as_ref().unwrap().signature));
+}
+
+#[test]
+fn test_pipeline_assertions_attached_to_every_manifest() {
+    let manifests = run_with_policy().unwrap();
+    let labels = |p: &Provenance| -> Vec<String> {
+        p.assertions.iter().map(|a| a.label.clone()).collect()
+    };
+    assert_eq!(labels(&manifests[0]), ["org.example.policy"]);
+    assert_eq!(labels(&manifests[1]), ["org.example.policy", "c2pa.transform"]);
+}
+
+#[c2pa_pipeline(generator = "policy")]
+fn run_nested_with_policy() -> Result<C2pa<u32, Verified>, TransformError> {
+    with_ctx(|ctx| {
+        ctx.assertions
+            .push(CustomAssertion::json("org.example.policy", r#"{"tier":2}"#))
+    });
+    inherit_generator()
+}
+
+#[test]
+fn test_sub_pipeline_inherits_assertions() {
+    let nested = run_nested_with_policy().unwrap();
+    let labels: Vec<_> = nested.provenance().assertions.iter().map(|a| &a.label).collect();
+    assert_eq!(labels, ["org.example.policy", "c2pa.transform"]);
+    assert_eq!(nested.provenance().assertions[0].data, br#"{"tier":2}"#);
+}
+
+#[test]
+fn test_pipeline_required_timestamp_fails_without_authority() {
+    assert!(matches!(run_requiring_timestamp(), Err(TransformError::Signing(_))));
+}
+
+#[c2pa_pipeline(generator = "digests", hash = "blake3")]
+fn run_with_blake3() -> Result<Vec<Provenance>, TransformError> {
+    let start = origin_value_c2pa()?;
+    let tripled = triple_c2pa(&start)?;
+    let nested = inherit_generator()?;
+    Ok(vec![start.provenance().clone(), tripled.provenance().clone(), nested.provenance().clone()])
+}
+
+#[test]
+fn test_pipeline_hash_alg_applies_to_every_manifest() {
+    for provenance in run_with_blake3().unwrap() {
+        assert_eq!(provenance.claim_hash.alg(), HashAlg::Blake3);
+        let AssetBinding::Hash(hash) = &provenance.asset_binding else {
+            panic!("expected a hash binding");
+        };
+        assert_eq!(hash.alg(), HashAlg::Blake3);
+    }
+    // Outside the pipeline the default is unchanged
+    assert_eq!(inherit_generator().unwrap().provenance().claim_hash.alg(), HashAlg::Sha256);
+}
+
+// ============================================================================
+// signer selection tests
+// ============================================================================
+
+fn pipeline_signer() -> Ed25519Signer {
+    Ed25519Signer::from_raw(&[21u8; 32]).unwrap()
+}
+
+fn audit_signer() -> Ed25519Signer {
+    Ed25519Signer::from_raw(&[22u8; 32]).unwrap()
+}
+
+#[c2pa_transform(name = "audited", signer = audit_signer())]
+fn audited(x: &u32) -> u32 {
+    x + 1
+}
+
+#[c2pa_pipeline(generator = "signed", signer = pipeline_signer())]
+fn run_signed() -> Result<Vec<Provenance>, TransformError> {
+    let start = origin_value_c2pa()?;
+    let tripled = triple_c2pa(&start)?;
+    let nested = inherit_generator()?;
+    let audited = audited_c2pa(&tripled)?;
+    Ok([start.provenance(), tripled.provenance(), nested.provenance(), audited.provenance()]
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+#[test]
+fn test_pipeline_signer_signs_every_node() {
+    let manifests = run_signed().unwrap();
+    let key_id = |p: &Provenance| p.signature.as_ref().unwrap().key_id.clone();
+
+    // Source, transform and nested sub-pipeline all use the pipeline signer
+    let pipeline_key = pipeline_signer().key_id();
+    for provenance in &manifests[..3] {
+        assert_eq!(key_id(provenance), pipeline_key);
+    }
+    // The transform-level override wins
+    assert_eq!(key_id(&manifests[3]), audit_signer().key_id());
+
+    let trusted = TrustedKeys::new()
+        .add_key(pipeline_signer().verifier())
+        .add_key(audit_signer().verifier());
+    let report = manifests
+        .iter()
+        .cloned()
+        .fold(ChainVerifier::new(&trusted), ChainVerifier::add_manifest)
+        .verify(&manifests[3].claim_hash);
+    assert!(report.is_valid(), "{}", report);
+}
+
+#[test]
+fn test_default_signer_is_placeholder() {
+    let value = inherit_generator().unwrap();
+    let signature = value.provenance().signature.as_ref().unwrap();
+    assert_eq!(signature.algorithm, SigningAlg::Placeholder);
+}
+
+// ============================================================================
+// parallel tests
+// ============================================================================
+
+// `&Vec<u8>` so the wrapper takes `&C2pa<Vec<u8>, Verified>`
+#[allow(clippy::ptr_arg)]
+#[c2pa_transform(name = "checksum")]
+fn checksum(chunk: &Vec<u8>) -> u64 {
+    chunk.iter().map(|b| *b as u64).sum()
+}
+
+#[c2pa_pipeline(generator = "batch")]
+fn run_batch(data: Vec<u8>) -> Result<Vec<C2pa<u64, Verified>>, TransformError> {
+    let input = C2paBuilder::new(data).generator("batch").sign(&TestSigner)?;
+    par_map(&input, 3, checksum_c2pa)
+}
+
+#[test]
+fn test_par_map_runs_wrappers_on_workers() {
+    let results = run_batch((1..=10).collect()).unwrap();
+
+    let sums: Vec<u64> = results.iter().map(|r| *r.payload()).collect();
+    assert_eq!(sums, vec![6, 15, 24, 10]);
+
+    for result in &results {
+        // checksum <- chunk <- input: provenance survives the thread hop
+        assert_eq!(result.provenance().generator, "batch");
+        assert_eq!(result.provenance().ingredients.len(), 1);
+    }
+}
+
+#[allow(clippy::ptr_arg)]
+#[c2pa_transform(name = "scaled_checksum", record(params(scale)))]
+fn scaled_checksum(chunk: &Vec<u8>, scale: u64) -> u64 {
+    chunk.iter().map(|b| *b as u64 * scale).sum()
+}
+
+#[test]
+fn test_par_map_openings_reach_pipeline() {
+    with_new_ctx("batch", || {
+        let input = C2paBuilder::new((1..=10).collect::<Vec<u8>>()).sign(&TestSigner).unwrap();
+        let results = par_map(&input, 3, |chunk| scaled_checksum_c2pa(chunk, 2)).unwrap();
+
+        let openings = with_ctx(|ctx| ctx.take_param_openings());
+        assert_eq!(openings.len(), results.len());
+        for result in &results {
+            let claim_hash = &result.provenance().claim_hash;
+            let (_, opening) = openings.iter().find(|(hash, _)| hash == claim_hash).unwrap();
+            assert!(opening.opens(result.provenance()));
+        }
+    });
+}
+
+#[test]
+fn test_par_map_propagates_errors() {
+    let result = with_new_ctx("batch", || {
+        let input = C2paBuilder::new(vec![1u8, 2, 3]).sign(&TestSigner).unwrap();
+        par_map(&input, 1, |chunk| -> Result<C2pa<u8, Verified>, TransformError> {
+            if chunk.payload()[0] == 2 {
+                Err(TransformError::C2pa("bad chunk".into()))
+            } else {
+                C2paBuilder::new(chunk.payload()[0]).sign(&TestSigner)
+            }
+        })
+    });
+    assert!(result.is_err());
+}
+
+// ============================================================================
+// async pipeline tests
+// ============================================================================
+
+#[c2pa_transform(name = "slow_triple")]
+async fn slow_triple(x: &u32) -> u32 {
+    // Give the executor a chance to move the task to another thread
+    tokio::task::yield_now().await;
+    x * 3
+}
+
+#[c2pa_source]
+async fn fetch_value() -> u32 {
+    tokio::task::yield_now().await;
+    5
+}
+
+#[c2pa_pipeline(generator = "async_pipeline")]
+async fn run_async_pipeline(label: String) -> Result<C2pa<u32, Verified>, TransformError> {
+    let start = fetch_value_c2pa().await?;
+    let once = slow_triple_c2pa(&start).await?;
+    let generator = with_ctx(|ctx| ctx.generator.clone());
+    assert_eq!(generator, "async_pipeline", "{}", label);
+    let twice = slow_triple_c2pa(&once).await?;
+    Ok(twice)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_async_pipeline_context_follows_task() {
+    let tasks: Vec<_> = (0..32)
+        .map(|i| tokio::spawn(run_async_pipeline(format!("task {}", i))))
+        .collect();
+
+    for task in tasks {
+        let result = task.await.unwrap().unwrap();
+        assert_eq!(*result.payload(), 45);
+        assert_eq!(result.provenance().generator, "async_pipeline");
+        assert_eq!(result.provenance().ingredients.len(), 1);
+    }
+}
+
+#[tokio::test]
+async fn test_async_context_is_not_left_on_thread() {
+    let pending = run_async_pipeline("pending".into());
+    assert!(!has_ctx());
+    pending.await.unwrap();
+    assert!(!has_ctx());
+}
@@ -0,0 +1,10 @@
+//! This should fail to compile because `componetOf` is not a relationship.
+
+use c2pa_primitives::*;
+
+#[c2pa_composite(name = "overlay", relationship(layer = "componetOf"))]
+fn overlay(base: &u32, layer: &u32) -> u32 {
+    base | layer
+}
+
+fn main() {}
@@ -13,8 +13,8 @@ fn main() {
         42,
         Provenance::root(
             "test",
-            ClaimHash([0; 32]),
-            AssetBinding::Hash(ContentHash([0; 32])),
+            ClaimHash::from_bytes([0; 32]),
+            AssetBinding::Hash(ContentHash::from_bytes([0; 32])),
         ),
     );
 